@@ -121,6 +121,21 @@ impl CrushCommand for Closure {
     fn arguments(&self) -> &Vec<ArgumentDescription> {
         &self.arguments
     }
+
+    fn define(&self, name: TrackedString, signature: Option<Vec<Parameter>>) -> CrushResult<Command> {
+        if signature.is_some() && self.signature.is_some() {
+            return argument_error_legacy("The closure already has a signature");
+        }
+        Ok(Box::from(Closure {
+            name: Some(name),
+            signature: signature.or_else(|| self.signature.clone()),
+            job_definitions: self.job_definitions.clone(),
+            env: self.env.clone(),
+            short_help: self.short_help.clone(),
+            long_help: self.long_help.clone(),
+            arguments: self.arguments.clone(),
+        }))
+    }
 }
 
 struct ClosureSerializer<'a> {
@@ -527,7 +542,12 @@ impl Help for Closure {
     }
 
     fn long_help(&self) -> Option<String> {
-        Some(self.long_help.clone())
+        let source = format!("    Source:\n\n    {{{}}}", self);
+        if self.long_help.is_empty() {
+            Some(source)
+        } else {
+            Some(format!("{}\n\n{}", self.long_help, source))
+        }
     }
 }
 
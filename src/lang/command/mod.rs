@@ -1,10 +1,11 @@
 mod closure;
 
-use crate::lang::argument::ArgumentDefinition;
+use crate::lang::argument::{Argument, ArgumentDefinition};
 use crate::lang::errors::{error, CrushResult};
 use crate::lang::execution_context::{CompileContext, CommandContext};
 use crate::lang::help::Help;
 use crate::lang::job::Job;
+use crate::lang::pipe::pipe;
 use crate::lang::data::scope::Scope;
 use crate::lang::serialization::model;
 use crate::lang::serialization::model::{element, Element};
@@ -75,6 +76,12 @@ pub trait CrushCommand: Help {
     fn bind(&self, this: Value) -> Command;
     fn output<'a>(&'a self, input: &'a OutputType) -> Option<&'a ValueType>;
     fn arguments(&self) -> &Vec<ArgumentDescription>;
+
+    /** Return a copy of this command with the specified name, and, optionally, the specified
+    signature. Only closures can be given a new name. */
+    fn define(&self, _name: TrackedString, _signature: Option<Vec<Parameter>>) -> CrushResult<Command> {
+        error("Only closures can be defined as named commands")
+    }
 }
 
 pub trait TypeMap {
@@ -190,6 +197,14 @@ impl dyn CrushCommand {
         })
     }
 
+    pub fn partial(command: Command, arguments: Vec<Argument>) -> Command {
+        Box::from(PartialCommand { command, arguments })
+    }
+
+    pub fn compose(first: Command, second: Command) -> Command {
+        Box::from(ComposedCommand { first, second })
+    }
+
     pub fn deserialize(
         id: usize,
         elements: &[Element],
@@ -504,3 +519,175 @@ impl Help for BoundCommand {
         self.command.long_help()
     }
 }
+
+/**
+A command with some of its arguments already supplied. The bound arguments are prepended to
+the arguments of every invocation.
+*/
+pub struct PartialCommand {
+    command: Command,
+    arguments: Vec<Argument>,
+}
+
+impl CrushCommand for PartialCommand {
+    fn invoke(&self, mut context: CommandContext) -> CrushResult<()> {
+        let mut arguments = self.arguments.clone();
+        arguments.append(&mut context.arguments);
+        context.arguments = arguments;
+        self.command.invoke(context)
+    }
+
+    fn can_block(&self, arguments: &[ArgumentDefinition], context: &mut CompileContext) -> bool {
+        self.command.can_block(arguments, context)
+    }
+
+    fn name(&self) -> &str {
+        self.command.name()
+    }
+
+    fn copy(&self) -> Command {
+        Box::from(PartialCommand {
+            command: self.command.copy(),
+            arguments: self.arguments.clone(),
+        })
+    }
+
+    fn help(&self) -> &dyn Help {
+        self.command.help()
+    }
+
+    fn serialize(
+        &self,
+        _elements: &mut Vec<Element>,
+        _state: &mut SerializationState,
+    ) -> CrushResult<usize> {
+        error("Partially applied commands can not be serialized")
+    }
+
+    fn bind(&self, this: Value) -> Command {
+        Box::from(PartialCommand {
+            command: self.command.bind(this),
+            arguments: self.arguments.clone(),
+        })
+    }
+
+    fn output<'a>(&'a self, input: &'a OutputType) -> Option<&'a ValueType> {
+        self.command.output(input)
+    }
+
+    fn arguments(&self) -> &Vec<ArgumentDescription> {
+        self.command.arguments()
+    }
+}
+
+impl Help for PartialCommand {
+    fn signature(&self) -> String {
+        self.command.signature()
+    }
+
+    fn short_help(&self) -> String {
+        self.command.short_help()
+    }
+
+    fn long_help(&self) -> Option<String> {
+        self.command.long_help()
+    }
+}
+
+/**
+Two commands joined into a pipeline. The output of the first command is used as the input of
+the second one.
+*/
+pub struct ComposedCommand {
+    first: Command,
+    second: Command,
+}
+
+impl CrushCommand for ComposedCommand {
+    fn invoke(&self, context: CommandContext) -> CrushResult<()> {
+        let (sender, receiver) = pipe();
+        let global_state = context.global_state.clone();
+        let first = self.first.copy();
+        let first_context = CommandContext {
+            input: context.input,
+            output: sender,
+            arguments: context.arguments,
+            scope: context.scope.clone(),
+            this: None,
+            global_state: context.global_state.clone(),
+        };
+        let id = global_state
+            .threads()
+            .spawn("compose", move || first.invoke(first_context))?;
+        let res = self.second.invoke(CommandContext {
+            input: receiver,
+            output: context.output,
+            arguments: vec![],
+            scope: context.scope,
+            this: None,
+            global_state: context.global_state,
+        });
+        global_state.threads().join_one(id, &global_state.printer());
+        res
+    }
+
+    fn can_block(&self, arguments: &[ArgumentDefinition], context: &mut CompileContext) -> bool {
+        self.first.can_block(arguments, context) || self.second.can_block(&[], context)
+    }
+
+    fn name(&self) -> &str {
+        "composed command"
+    }
+
+    fn copy(&self) -> Command {
+        Box::from(ComposedCommand {
+            first: self.first.copy(),
+            second: self.second.copy(),
+        })
+    }
+
+    fn help(&self) -> &dyn Help {
+        self
+    }
+
+    fn serialize(
+        &self,
+        _elements: &mut Vec<Element>,
+        _state: &mut SerializationState,
+    ) -> CrushResult<usize> {
+        error("Composed commands can not be serialized")
+    }
+
+    fn bind(&self, this: Value) -> Command {
+        Box::from(BoundCommand {
+            command: self.copy(),
+            this,
+        })
+    }
+
+    fn output<'a>(&'a self, input: &'a OutputType) -> Option<&'a ValueType> {
+        self.second.output(input)
+    }
+
+    fn arguments(&self) -> &Vec<ArgumentDescription> {
+        self.first.arguments()
+    }
+}
+
+impl Help for ComposedCommand {
+    fn signature(&self) -> String {
+        format!("{} | {}", self.first.signature(), self.second.signature())
+    }
+
+    fn short_help(&self) -> String {
+        format!(
+            "The output of {} passed as the input of {}",
+            self.first.name(),
+            self.second.name()
+        )
+    }
+
+    fn long_help(&self) -> Option<String> {
+        None
+    }
+}
@@ -8,7 +8,9 @@ use crate::util::identity_arc::Identity;
 use ordered_map::OrderedMap;
 use std::cmp::max;
 use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::fmt::{Display, Formatter};
+use std::collections::HashSet;
 
 /**
   This is where we store variables, including functions.
@@ -152,6 +154,36 @@ impl ScopeLoader {
     }
 }
 
+/**
+  Set when a scope is stopped from the outside, e.g. by the timeout command. Every child scope
+  gets its own signal, linked to the signal of the scope it was called from, so that checking if a
+  scope or any of its callers has been stopped never needs to lock any other scope.
+*/
+struct StopSignal {
+    stopped: AtomicBool,
+    caller: Option<Arc<StopSignal>>,
+}
+
+impl StopSignal {
+    fn new(caller: Option<Arc<StopSignal>>) -> Arc<StopSignal> {
+        Arc::from(StopSignal {
+            stopped: AtomicBool::new(false),
+            caller,
+        })
+    }
+
+    fn is_set(&self) -> bool {
+        if self.stopped.load(Ordering::Relaxed) {
+            return true;
+        }
+        if self.caller.as_ref().map(|c| c.is_set()).unwrap_or(false) {
+            self.stopped.store(true, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+}
+
 pub struct ScopeData {
     /** This is the parent scope used to perform variable name resolution. If a variable lookup
     fails in the current scope, it proceeds to this scope. This is usually the scope in which this
@@ -173,6 +205,15 @@ pub struct ScopeData {
     /** The actual data of this scope. */
     pub mapping: OrderedMap<String, Value>,
 
+    /** The declared type of those variables in this scope that were declared with an explicit
+    type. Variables not in this map may only be reassigned to values of the same type as their
+    current value. */
+    pub declared_types: OrderedMap<String, ValueType>,
+
+    /** The names of all variables in this scope that are constants, i.e. that can not be
+    reassigned or removed. */
+    pub constants: HashSet<String>,
+
     /** True if this scope is a loop. Required to implement the break/continue commands.*/
     pub is_loop: bool,
 
@@ -180,6 +221,9 @@ pub struct ScopeData {
     called.  */
     pub is_stopped: bool,
 
+    /** Set if this scope, or any scope it was called from, has been stopped by the stop method. */
+    stop_signal: Arc<StopSignal>,
+
     /** True if this scope can not be further modified. Note that mutable variables in it, e.g.
     lists can still be modified. */
     pub is_readonly: bool,
@@ -204,7 +248,10 @@ impl ScopeData {
             is_loop,
             uses: Vec::new(),
             mapping: OrderedMap::new(),
+            declared_types: OrderedMap::new(),
+            constants: HashSet::new(),
             is_stopped: false,
+            stop_signal: StopSignal::new(None),
             is_readonly: false,
             name,
             description,
@@ -227,7 +274,10 @@ impl ScopeData {
             is_loop,
             uses: Vec::new(),
             mapping: OrderedMap::new(),
+            declared_types: OrderedMap::new(),
+            constants: HashSet::new(),
             is_stopped: false,
+            stop_signal: StopSignal::new(None),
             is_readonly: false,
             name,
             description,
@@ -235,6 +285,14 @@ impl ScopeData {
             loader: Some(loader),
         }
     }
+
+    /** True if the specified value may be assigned to the existing variable of the given name. */
+    fn accepts(&self, name: &str, value: &Value) -> bool {
+        match self.declared_types.get(name) {
+            Some(value_type) => value_type.is(value),
+            None => self.mapping[name].value_type() == value.value_type(),
+        }
+    }
 }
 
 impl Clone for ScopeData {
@@ -245,7 +303,10 @@ impl Clone for ScopeData {
             is_loop: self.is_loop,
             uses: self.uses.clone(),
             mapping: self.mapping.clone(),
+            declared_types: self.declared_types.clone(),
+            constants: self.constants.clone(),
             is_stopped: self.is_stopped,
+            stop_signal: self.stop_signal.clone(),
             is_readonly: self.is_readonly,
             name: self.name.clone(),
             description: self.description.clone(),
@@ -289,8 +350,11 @@ impl Scope {
                 calling_scope: None,
                 uses: vec![],
                 mapping: OrderedMap::new(),
+                declared_types: OrderedMap::new(),
+                constants: HashSet::new(),
                 is_loop,
                 is_stopped,
+                stop_signal: StopSignal::new(None),
                 is_readonly,
                 name,
                 description,
@@ -301,14 +365,16 @@ impl Scope {
     }
 
     pub fn create_child(&self, caller: &Scope, is_loop: bool) -> Scope {
+        let mut data = ScopeData::new(
+            Some(self.clone()),
+            Some(caller.clone()),
+            is_loop,
+            None,
+            None,
+        );
+        data.stop_signal = StopSignal::new(Some(caller.data.lock().unwrap().stop_signal.clone()));
         Scope {
-            data: Arc::from(Mutex::new(ScopeData::new(
-                Some(self.clone()),
-                Some(caller.clone()),
-                is_loop,
-                None,
-                None,
-            ))),
+            data: Arc::from(Mutex::new(data)),
         }
     }
 
@@ -384,8 +450,21 @@ impl Scope {
         Ok(())
     }
 
+    /**
+    Stop execution of this scope and of every scope called from it, e.g. because a timeout
+    expired. Unlike do_exit, this does not propagate to the calling scope.
+    */
+    pub fn stop(&self) {
+        self.lock().unwrap().stop_signal.stopped.store(true, Ordering::Relaxed);
+    }
+
+    /**
+    True if this scope has been stopped, or if it or any scope it was called from has been
+    stopped by the stop method.
+    */
     pub fn is_stopped(&self) -> bool {
-        self.lock().unwrap().is_stopped
+        let data = self.lock().unwrap();
+        data.is_stopped || data.stop_signal.is_set()
     }
 
     fn lock(&self) -> CrushResult<MutexGuard<ScopeData>> {
@@ -538,6 +617,12 @@ impl Scope {
     }
 
     pub fn declare(&self, name: &str, value: Value) -> CrushResult<()> {
+        self.declare_with(name, value, |_| {})
+    }
+
+    /** Declare a new variable and let the callback update the scope while it is still locked,
+    so that no other thread can observe the variable before the update is done. */
+    fn declare_with(&self, name: &str, value: Value, update: impl FnOnce(&mut ScopeData)) -> CrushResult<()> {
         if name.starts_with("__") {
             return argument_error_legacy(format!("Illegal operation: Can't declare variables beginning with double underscores. ({})", name));
         }
@@ -549,6 +634,7 @@ impl Scope {
             return error(format!("Variable {{{}}} already exists", name).as_str());
         }
         data.mapping.insert(name.to_string(), value);
+        update(&mut data);
         Ok(())
     }
 
@@ -560,26 +646,54 @@ impl Scope {
         if data.is_readonly {
             return error("Scope is read only");
         }
+        if data.constants.contains(name) {
+            return error("Cannot reassign constant");
+        }
+        data.declared_types.remove(name);
         data.mapping.insert(name.to_string(), value);
         Ok(())
     }
 
+    /** Declare a new variable that can not be reassigned or removed. */
+    pub fn declare_constant(&self, name: &str, value: Value) -> CrushResult<()> {
+        self.declare_with(name, value, |data| {
+            data.constants.insert(name.to_string());
+        })
+    }
+
+    /** Declare a new variable with an explicit type. Later reassignments of the variable are
+    allowed as long as the new value is of the declared type, so a variable declared with the
+    type any can be reassigned to a value of any type. */
+    pub fn declare_typed(&self, name: &str, value_type: ValueType, value: Value) -> CrushResult<()> {
+        if !value_type.is(&value) {
+            return argument_error_legacy(format!(
+                "Can't declare variable {{{}}} of type {} with a value of type {}",
+                name, value_type, value.value_type()));
+        }
+        self.declare_with(name, value, |data| {
+            data.declared_types.insert(name.to_string(), value_type);
+        })
+    }
+
     pub fn set(&self, name: &str, value: Value) -> CrushResult<()> {
         if name.starts_with("__") {
             return argument_error_legacy(format!("Illegal operation: Can't set variables beginning with double underscores. ({})", name));
         }
         let mut data = self.lock()?;
         if !data.mapping.contains_key(name) {
-            match data.parent_scope.clone() {
-                Some(p) => {
-                    drop(data);
-                    p.set(name, value)
-                }
+            let uses = data.uses.clone();
+            let parent = data.parent_scope.clone();
+            drop(data);
+            Scope::check_not_imported(name, &uses, "modify")?;
+            match parent {
+                Some(p) => p.set(name, value),
                 None => error(format!("Unknown variable {}", name).as_str()),
             }
         } else if data.is_readonly {
             error(format!("Tried to modify {}, a member of a read-only scope", name))
-        } else if data.mapping[name].value_type() != value.value_type() {
+        } else if data.constants.contains(name) {
+            error("Cannot reassign constant")
+        } else if !data.accepts(name, &value) {
             error(format!("Type mismatch when reassigning variable {{{}}}. Use `var:unset \"{}\"` to remove old variable.", name, name).as_str())
         } else {
             data.mapping.insert(name.to_string(), value);
@@ -616,21 +730,42 @@ impl Scope {
         }
         let mut data = self.lock()?;
         if !data.mapping.contains_key(key) {
-            match data.parent_scope.clone() {
-                Some(p) => {
-                    drop(data);
-                    p.remove_here(key)
-                }
+            let uses = data.uses.clone();
+            let parent = data.parent_scope.clone();
+            drop(data);
+            Scope::check_not_imported(key, &uses, "remove")?;
+            match parent {
+                Some(p) => p.remove_here(key),
                 None => Ok(None),
             }
         } else {
             if data.is_readonly {
-                return Ok(None);
+                return error(format!("Tried to remove {}, a member of a read-only scope", key));
+            }
+            if data.constants.contains(key) {
+                return error(format!("Cannot remove constant {}", key));
             }
+            data.declared_types.remove(key);
             Ok(data.mapping.remove(key))
         }
     }
 
+    /** Variables that are visible through a used scope can be read, but not modified, since
+    that would modify the imported scope for everyone using it. */
+    fn check_not_imported(name: &str, uses: &[Scope], operation: &str) -> CrushResult<()> {
+        for used in uses {
+            if used.get(name)?.is_some() {
+                return error(format!(
+                    "Can't {} {}, it is imported from the scope {}. Declare a new variable to shadow it instead.",
+                    operation,
+                    name,
+                    used.full_path().map(|p| p.join(":")).unwrap_or_else(|_| "<anonymous>".to_string()),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     pub fn get(&self, name: &str) -> CrushResult<Option<Value>> {
         let data = self.lock()?;
         match lookup(name, self, &data) {
@@ -661,12 +796,36 @@ impl Scope {
         self.lock().unwrap().uses.push(other.clone());
     }
 
+    /** Returns all scopes that are searched during lookups in addition to this scope and its
+    parents, in the order they are searched. */
+    pub fn uses(&self) -> CrushResult<Vec<Scope>> {
+        let data = self.lock()?;
+        let mut res = data.uses.clone();
+        let parent = data.parent_scope.clone();
+        drop(data);
+        if let Some(p) = parent {
+            res.append(&mut p.uses()?);
+        }
+        Ok(res)
+    }
+
     pub fn dump(&self) -> CrushResult<OrderedMap<String, ValueType>> {
         let mut res = OrderedMap::new();
         self.dump_internal(&mut res)?;
         Ok(res)
     }
 
+    /** Like dump, but only includes the variables declared directly in this scope, not those of
+    parent scopes or used scopes. */
+    pub fn dump_local(&self) -> CrushResult<OrderedMap<String, ValueType>> {
+        let data = self.lock()?;
+        let mut res = OrderedMap::new();
+        for (k, v) in data.mapping.iter() {
+            res.insert(k.to_string(), v.value_type());
+        }
+        Ok(res)
+    }
+
     fn dump_internal(&self, map: &mut OrderedMap<String, ValueType>) -> CrushResult<()> {
         let p = self.lock()?.parent_scope.clone();
         if let Some(p) = p {
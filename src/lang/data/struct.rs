@@ -20,6 +20,11 @@ lazy_static! {
         ColumnType::new("name", ValueType::String),
         ColumnType::new("value", ValueType::Any),
     ];
+    pub static ref INDEXED_STRUCT_STREAM_TYPE: Vec<ColumnType> = vec![
+        ColumnType::new("idx", ValueType::Integer),
+        ColumnType::new("name", ValueType::String),
+        ColumnType::new("value", ValueType::Any),
+    ];
 }
 
 #[derive(Clone)]
@@ -189,6 +194,10 @@ impl Struct {
         }
     }
 
+    /**
+    All fields of this struct and its parents. Local fields come first, in the order they were
+    added, followed by the fields of the parent chain. Fields shadowed by a child are omitted.
+     */
     pub fn map(&self) -> OrderedMap<String, Value> {
         let mut map = OrderedMap::new();
         self.fill_map(&mut map);
@@ -237,6 +246,10 @@ impl Struct {
         })
     }
 
+    pub fn parent(&self) -> Option<Struct> {
+        self.data.lock().unwrap().parent.clone()
+    }
+
     pub fn set_parent(&self, parent: Option<Struct>) {
         self.data.lock().unwrap().parent = parent;
     }
@@ -268,6 +281,7 @@ impl Display for Struct {
 pub struct StructReader {
     idx: usize,
     rows: Vec<(String, Value)>,
+    indexed: bool,
 }
 
 impl StructReader {
@@ -275,6 +289,17 @@ impl StructReader {
         StructReader {
             idx: 0,
             rows: s.map().drain().collect(),
+            indexed: false,
+        }
+    }
+
+    /**
+    Create a reader that also emits the position of each field as a leading idx column.
+     */
+    pub fn indexed(s: Struct) -> StructReader {
+        StructReader {
+            indexed: true,
+            ..StructReader::new(s)
         }
     }
 }
@@ -288,7 +313,11 @@ impl CrushStream for StructReader {
         let (k, v) = self
             .rows
             .replace(self.idx - 1, ("".to_string(), Value::Empty()));
-        Ok(Row::new(vec![Value::String(k), v]))
+        if self.indexed {
+            Ok(Row::new(vec![Value::Integer(self.idx as i128 - 1), Value::String(k), v]))
+        } else {
+            Ok(Row::new(vec![Value::String(k), v]))
+        }
     }
 
     fn read_timeout(
@@ -302,6 +331,10 @@ impl CrushStream for StructReader {
     }
 
     fn types(&self) -> &[ColumnType] {
-        &STRUCT_STREAM_TYPE
+        if self.indexed {
+            &INDEXED_STRUCT_STREAM_TYPE
+        } else {
+            &STRUCT_STREAM_TYPE
+        }
     }
 }
@@ -9,6 +9,7 @@ pub enum CrushErrorType {
     InvalidArgument(String),
     InvalidData(String),
     GenericError(String),
+    IOError(String),
     BlockError,
     SendError,
     EOFError,
@@ -47,7 +48,8 @@ impl CrushError {
         match &self.error_type {
             InvalidArgument(s)
             | InvalidData(s)
-            | GenericError(s) => s.clone(),
+            | GenericError(s)
+            | IOError(s) => s.clone(),
             BlockError => "Block error".to_string(),
             SendError => "Send error".to_string(),
             EOFError => "EOF error".to_string(),
@@ -74,6 +76,21 @@ impl CrushError {
         }
     }
 
+    pub fn with_prefix(&self, prefix: &str) -> CrushError {
+        let error_type = match &self.error_type {
+            InvalidArgument(s) => InvalidArgument(format!("{}: {}", prefix, s)),
+            InvalidData(s) => InvalidData(format!("{}: {}", prefix, s)),
+            GenericError(s) => GenericError(format!("{}: {}", prefix, s)),
+            IOError(s) => IOError(format!("{}: {}", prefix, s)),
+            t => t.clone(),
+        };
+        CrushError {
+            error_type,
+            location: self.location,
+            definition: self.definition.clone(),
+        }
+    }
+
     pub fn with_location(&self, l: Location) -> CrushError {
         let location = if let Some(old) = self.location() {
             if old.len() < l.len() {
@@ -178,6 +195,14 @@ pub fn error<T>(message: impl Into<String>) -> CrushResult<T> {
     })
 }
 
+pub fn io_error<T>(message: impl Into<String>) -> CrushResult<T> {
+    Err(CrushError {
+        error_type: IOError(message.into()),
+        location: None,
+        definition: None,
+    })
+}
+
 pub fn to_crush_error<T, E: Error>(result: Result<T, E>) -> Result<T, CrushError> {
     match result {
         Ok(v) => Ok(v),
@@ -51,6 +51,7 @@ impl Files {
             match input.recv()? {
                 Value::BinaryInputStream(b) => Ok(b),
                 Value::Binary(b) => Ok(BinaryReader::vec(&b)),
+                Value::String(s) => Ok(BinaryReader::vec(&s.into_bytes())),
                 _ => argument_error_legacy("Expected either a file to read or binary pipe io"),
             }
         } else {
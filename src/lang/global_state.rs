@@ -17,6 +17,7 @@ pub struct GlobalState {
     printer: Printer,
     exit_status: Arc<Mutex<Option<i32>>>,
     prompt: Arc<Mutex<Option<Command>>>,
+    history: Arc<Mutex<Vec<String>>>,
     parser: Parser,
 }
 
@@ -32,6 +33,7 @@ impl GlobalState {
             printer,
             exit_status: Arc::from(Mutex::new(None)),
             prompt: Arc::from(Mutex::new(None)),
+            history: Arc::from(Mutex::new(Vec::new())),
             parser: Parser::new(),
         })
     }
@@ -53,6 +55,16 @@ impl GlobalState {
         &self.printer
     }
 
+    /**
+    Return a copy of this state that reports output and errors through the specified printer.
+    */
+    pub fn with_printer(&self, printer: Printer) -> GlobalState {
+        GlobalState {
+            printer,
+            ..self.clone()
+        }
+    }
+
     pub fn locale(&self) -> SystemLocale {
         let data = self.data.lock().unwrap();
         data.locale.clone()
@@ -82,4 +94,19 @@ impl GlobalState {
         let data = self.prompt.lock().unwrap();
         data.as_ref().map(|a| a.copy())
     }
-}
\ No newline at end of file
+
+    pub fn add_history(&self, line: &str) {
+        let mut data = self.history.lock().unwrap();
+        data.push(line.to_string());
+    }
+
+    pub fn set_history(&self, history: Vec<String>) {
+        let mut data = self.history.lock().unwrap();
+        *data = history;
+    }
+
+    pub fn history(&self) -> Vec<String> {
+        let data = self.history.lock().unwrap();
+        data.clone()
+    }
+}
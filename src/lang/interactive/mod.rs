@@ -72,6 +72,18 @@ pub fn load_init(
     }
 }
 
+/// Commands like `crush:history --clear` modify the shared history, make sure the
+/// editor history reflects this before it is saved.
+fn sync_history<H: rustyline::Helper>(rl: &mut Editor<H>, global_state: &GlobalState) {
+    let history = global_state.history();
+    if history.len() != rl.history().len() {
+        rl.clear_history();
+        for line in history {
+            rl.add_history_entry(line);
+        }
+    }
+}
+
 pub fn run(
     global_env: Scope,
     pretty_printer: &ValueSender,
@@ -100,6 +112,7 @@ pub fn run(
     if let Ok(file) = crush_history_file() {
         let _ = rl.load_history(&file);
     }
+    global_state.set_history(rl.history().iter().cloned().collect());
     loop {
         let prompt = match execute_prompt(global_state.prompt(), &global_env, global_state) {
             Ok(s) => s,
@@ -114,6 +127,7 @@ pub fn run(
             Ok(cmd) if cmd.is_empty() => global_state.threads().reap(global_state.printer()),
             Ok(cmd) => {
                 rl.add_history_entry(&cmd);
+                global_state.add_history(&cmd);
                 global_state.threads().reap(global_state.printer());
                 global_state.printer().handle_error(
                     execute::string(
@@ -123,6 +137,7 @@ pub fn run(
                         global_state,
                     ));
                 global_state.threads().reap(global_state.printer());
+                sync_history(&mut rl, global_state);
                 if global_state.exit_status().is_some() {
                     break;
                 }
@@ -14,6 +14,12 @@ use lazy_static::lazy_static;
 
 pub type RecvTimeoutError = crossbeam::channel::RecvTimeoutError;
 
+/**
+The number of rows a stream can hold before sending to it blocks until the
+receiving end has caught up.
+ */
+pub const DEFAULT_STREAM_CAPACITY: usize = 128;
+
 lazy_static! {
     static ref BLACK_HOLE: ValueSender = {
         let (mut o, _) = pipe();
@@ -52,7 +58,19 @@ impl ValueSender {
     }
 
     pub fn initialize(&self, signature: Vec<ColumnType>) -> CrushResult<OutputStream> {
-        let (output, input) = streams(signature);
+        self.initialize_bounded(signature, DEFAULT_STREAM_CAPACITY)
+    }
+
+    /**
+    Like initialize, but the created stream will hold at most the specified number of rows
+    before sending blocks.
+     */
+    pub fn initialize_bounded(
+        &self,
+        signature: Vec<ColumnType>,
+        capacity: usize,
+    ) -> CrushResult<OutputStream> {
+        let (output, input) = bounded_streams(signature, capacity);
         self.send(Value::TableInputStream(input))?;
         Ok(output)
     }
@@ -182,7 +200,11 @@ pub fn pretty_printer_pipe() -> (ValueSender, ValueReceiver) {
 }
 
 pub fn streams(signature: Vec<ColumnType>) -> (OutputStream, InputStream) {
-    let (output, input) = bounded(128);
+    bounded_streams(signature, DEFAULT_STREAM_CAPACITY)
+}
+
+pub fn bounded_streams(signature: Vec<ColumnType>, capacity: usize) -> (OutputStream, InputStream) {
+    let (output, input) = bounded(capacity);
     (
         OutputStream {
             sender: output,
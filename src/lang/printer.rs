@@ -1,5 +1,5 @@
 use crate::lang::errors::{to_crush_error, CrushError, CrushResult, CrushErrorType};
-use crossbeam::bounded;
+use crossbeam::{bounded, unbounded};
 use crossbeam::Sender;
 use crossbeam::Receiver;
 use std::thread;
@@ -23,6 +23,7 @@ pub struct Printer {
     source: Option<(String, Location)>,
     sender: Sender<PrinterMessage>,
     pong_receiver: Receiver<()>,
+    captured_errors: Option<Sender<String>>,
 }
 
 // Too small terminals mean we can't meaningfully print anything, so assume at least this size
@@ -42,6 +43,7 @@ pub fn init() -> (Printer, JoinHandle<()>) {
             sender,
             pong_receiver,
             source: None,
+            captured_errors: None,
         },
         thread::Builder::new()
             .name("printer".to_string())
@@ -74,6 +76,7 @@ pub fn noop() -> (Printer, JoinHandle<()>) {
             sender,
             source: None,
             pong_receiver,
+            captured_errors: None,
         },
         thread::Builder::new()
             .name("printer:noop".to_string())
@@ -119,15 +122,39 @@ impl Printer {
             sender: self.sender.clone(),
             source: Some((def.to_string(), location)),
             pong_receiver: self.pong_receiver.clone(),
+            captured_errors: self.captured_errors.clone(),
         }
     }
 
+    /**
+    Return a printer that sends the messages of all reported errors to the returned receiver
+    instead of printing them. Other output is printed as usual.
+    */
+    pub fn capture_errors(&self) -> (Printer, Receiver<String>) {
+        let (sender, receiver) = unbounded();
+        (
+            Printer {
+                sender: self.sender.clone(),
+                source: self.source.clone(),
+                pong_receiver: self.pong_receiver.clone(),
+                captured_errors: Some(sender),
+            },
+            receiver,
+        )
+    }
+
     pub fn crush_error(&self, err: CrushError) {
-        let _ = self.sender.send(PrinterMessage::CrushError(err.with_source(&self.source)));
+        match &self.captured_errors {
+            Some(errors) => { let _ = errors.send(err.message()); }
+            None => { let _ = self.sender.send(PrinterMessage::CrushError(err.with_source(&self.source))); }
+        }
     }
 
     pub fn error(&self, err: &str) {
-        let _ = self.sender.send(PrinterMessage::Error(err.to_string()));
+        match &self.captured_errors {
+            Some(errors) => { let _ = errors.send(err.to_string()); }
+            None => { let _ = self.sender.send(PrinterMessage::Error(err.to_string())); }
+        }
     }
 
     pub fn width(&self) -> usize {
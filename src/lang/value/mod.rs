@@ -21,7 +21,7 @@ use crate::lang::data::{
     binary::BinaryReader, dict::Dict, dict::DictReader, list::List, list::ListReader,
     table::ColumnType, table::TableReader,
 };
-use crate::util::time::duration_format;
+use crate::util::time::{duration_format, parse_duration};
 use crate::{
     lang::errors::{error, to_crush_error},
     lang::data::table::Table,
@@ -344,9 +344,7 @@ impl Value {
             })),
             ValueType::String => Ok(Value::String(str_val)),
             ValueType::Time => error("invalid convert"),
-            ValueType::Duration => Ok(Value::Duration(Duration::seconds(to_crush_error(
-                i64::from_str(&str_val),
-            )?))),
+            ValueType::Duration => Ok(Value::Duration(parse_duration(&str_val)?)),
             ValueType::Command => error("invalid convert"),
             ValueType::TableInputStream(_) => error("invalid convert"),
             ValueType::TableOutputStream(_) => error("invalid convert"),
@@ -546,6 +544,7 @@ impl std::cmp::PartialEq for Value {
             (Value::Bool(val1), Value::Bool(val2)) => val1 == val2,
             (Value::Float(val1), Value::Float(val2)) => val1 == val2,
             (Value::Binary(val1), Value::Binary(val2)) => val1 == val2,
+            (Value::Empty(), Value::Empty()) => true,
             _ => false,
         }
     }
@@ -558,6 +557,15 @@ pub enum Alignment {
 
 impl std::cmp::PartialOrd for Value {
     fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+        // The empty value is equal to itself and less than any other value, so that e.g. rows
+        // with missing cells from an outer join can still be sorted.
+        match (self, other) {
+            (Value::Empty(), Value::Empty()) => return Some(Ordering::Equal),
+            (Value::Empty(), _) => return Some(Ordering::Less),
+            (_, Value::Empty()) => return Some(Ordering::Greater),
+            _ => {}
+        }
+
         let t1 = self.value_type();
         let t2 = other.value_type();
         if t1 != t2 {
@@ -711,4 +719,14 @@ mod tests {
         assert_eq!(Value::Integer(-1234).to_pretty_string(Grouping::Posix), "-1234");
         assert_eq!(Value::Integer(-123_456_789).to_pretty_string(Grouping::Posix), "-123456789");
     }
+
+    #[test]
+    fn test_empty_comparison() {
+        assert_eq!(Value::Empty(), Value::Empty());
+        assert_ne!(Value::Empty(), Value::Integer(0));
+        assert_ne!(Value::Empty(), Value::string(""));
+        assert_eq!(Value::Empty().partial_cmp(&Value::Empty()), Some(Ordering::Equal));
+        assert_eq!(Value::Empty().partial_cmp(&Value::Integer(-1)), Some(Ordering::Less));
+        assert_eq!(Value::string("").partial_cmp(&Value::Empty()), Some(Ordering::Greater));
+    }
 }
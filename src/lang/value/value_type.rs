@@ -64,6 +64,7 @@ impl ValueType {
             ValueType::TableOutputStream(_) => &types::table_output_stream::METHODS,
             ValueType::Binary => &types::binary::METHODS,
             ValueType::Scope => &types::scope::METHODS,
+            ValueType::Struct => &types::r#struct::METHODS,
             _ => &EMPTY_METHODS,
         }
     }
@@ -0,0 +1,137 @@
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::Known;
+use crate::lang::data::scope::Scope;
+use crate::lang::errors::{argument_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::pipe::{black_hole, empty_channel, pipe};
+use crate::lang::value::{Value, ValueType};
+use signature::signature;
+
+/// Fail with the default message, or with the custom one if one was given.
+fn fail(default: String, message: Option<String>, context: &CommandContext) -> CrushResult<()> {
+    let location = context.arguments[0].location;
+    argument_error(
+        format!("Assertion failed: {}", message.unwrap_or(default)),
+        location)
+}
+
+/// Run the command in a child scope and return its output.
+fn run(command: Command, context: &CommandContext) -> CrushResult<Value> {
+    let (sender, receiver) = pipe();
+    command.invoke(CommandContext {
+        input: empty_channel(),
+        output: sender,
+        arguments: vec![],
+        scope: context.scope.create_child(&context.scope, false),
+        this: None,
+        global_state: context.global_state.clone(),
+    })?;
+    receiver.recv()
+}
+
+#[signature(
+that,
+can_block = true,
+output = Known(ValueType::Empty),
+short = "Fail unless the condition is true",
+long = "The condition is either a boolean or a command returning a boolean.",
+example = "assert:that ((seq 10 | count) == 10) \"Expected ten rows\"",
+)]
+struct AssertThat {
+    #[description("the condition to check.")]
+    condition: Value,
+    #[description("the message to fail with.")]
+    message: Option<String>,
+}
+
+fn that(context: CommandContext) -> CrushResult<()> {
+    let cfg: AssertThat = AssertThat::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let value = match cfg.condition {
+        Value::Command(c) => run(c, &context)?,
+        v => v,
+    };
+    match value {
+        Value::Bool(true) => context.output.send(Value::Empty()),
+        Value::Bool(false) => fail("Condition is false".to_string(), cfg.message, &context),
+        v => fail(
+            format!("Expected condition to be a boolean, got a {}", v.value_type()),
+            cfg.message,
+            &context),
+    }
+}
+
+#[signature(
+eq,
+can_block = false,
+output = Known(ValueType::Empty),
+short = "Fail unless the two values are equal",
+example = "assert:eq (seq 10 | sum) 45",
+)]
+struct AssertEq {
+    #[description("the actual value.")]
+    actual: Value,
+    #[description("the expected value.")]
+    expected: Value,
+    #[description("the message to fail with.")]
+    message: Option<String>,
+}
+
+fn eq(context: CommandContext) -> CrushResult<()> {
+    let cfg: AssertEq = AssertEq::parse(context.arguments.clone(), &context.global_state.printer())?;
+    if cfg.actual == cfg.expected {
+        context.output.send(Value::Empty())
+    } else {
+        fail(
+            format!("Expected {}, got {}", cfg.expected, cfg.actual),
+            cfg.message,
+            &context)
+    }
+}
+
+#[signature(
+error,
+can_block = true,
+output = Known(ValueType::Empty),
+short = "Fail unless the body fails",
+long = "The body fails if it returns an error or if any command inside it reports an error.",
+long = "Errors reported by the body are not printed.",
+example = "assert:error {assert:eq 1 2}",
+)]
+struct AssertError {
+    #[description("the command that is expected to fail.")]
+    body: Command,
+    #[description("the message to fail with.")]
+    message: Option<String>,
+}
+
+fn error(context: CommandContext) -> CrushResult<()> {
+    let cfg: AssertError = AssertError::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let (printer, errors) = context.global_state.printer().capture_errors();
+    let result = cfg.body.invoke(CommandContext {
+        input: empty_channel(),
+        output: black_hole(),
+        arguments: vec![],
+        scope: context.scope.create_child(&context.scope, false),
+        this: None,
+        global_state: context.global_state.with_printer(printer),
+    });
+    if result.is_err() || errors.try_recv().is_ok() {
+        context.output.send(Value::Empty())
+    } else {
+        fail("Expected the body to fail".to_string(), cfg.message, &context)
+    }
+}
+
+pub fn declare(root: &Scope) -> CrushResult<()> {
+    root.create_namespace(
+        "assert",
+        "Commands for writing self checking scripts",
+        Box::new(move |env| {
+            AssertThat::declare(env)?;
+            AssertEq::declare(env)?;
+            AssertError::declare(env)?;
+            Ok(())
+        }),
+    )?;
+    Ok(())
+}
@@ -2,7 +2,9 @@ use crate::lang::errors::{argument_error_legacy, CrushResult};
 use crate::lang::execution_context::CommandContext;
 use crate::lang::data::scope::Scope;
 use crate::lang::pipe::{pipe, empty_channel};
-use crate::lang::value::Value;
+use crate::lang::value::{Value, ValueType};
+use crate::lang::command::OutputType::{Known, Unknown};
+use crate::lib::comp::not;
 
 pub fn and(mut context: CommandContext) -> CrushResult<()> {
     let mut res = true;
@@ -32,10 +34,12 @@ pub fn and(mut context: CommandContext) -> CrushResult<()> {
                             break;
                         }
                     }
-                    _ => return argument_error_legacy("Expected boolean values"),
+                    v => return argument_error_legacy(format!(
+                        "Expected condition to return a boolean, got a {}", v.value_type())),
                 }
             }
-            _ => return argument_error_legacy("Expected boolean values"),
+            v => return argument_error_legacy(format!(
+                "Expected a boolean or a command, got a {}", v.value_type())),
         }
     }
     context.output.send(Value::Bool(res))
@@ -70,15 +74,27 @@ pub fn or(mut context: CommandContext) -> CrushResult<()> {
                             break;
                         }
                     }
-                    _ => return argument_error_legacy("Expected boolean values"),
+                    v => return argument_error_legacy(format!(
+                        "Expected condition to return a boolean, got a {}", v.value_type())),
                 }
             }
-            _ => return argument_error_legacy("Expected boolean values"),
+            v => return argument_error_legacy(format!(
+                "Expected a boolean or a command, got a {}", v.value_type())),
         }
     }
     context.output.send(Value::Bool(res))
 }
 
+pub fn coalesce(mut context: CommandContext) -> CrushResult<()> {
+    for arg in context.arguments.drain(..) {
+        match arg.value {
+            Value::Empty() => {}
+            v => return context.output.send(v),
+        }
+    }
+    context.output.send(Value::Empty())
+}
+
 pub fn declare(root: &Scope) -> CrushResult<()> {
     root.create_namespace(
         "cond",
@@ -112,6 +128,46 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
                 vec![],
             )?;
 
+            env.declare_condition_command(
+                "all",
+                and,
+                "cond:all condition:(bool|command)... -> boolean",
+                "True if all arguments are true",
+                Some("    This is the same command as the and operator, see __and__ for details."),
+                vec![],
+            )?;
+
+            env.declare_condition_command(
+                "any",
+                or,
+                "cond:any condition:(bool|command)... -> boolean",
+                "True if any argument is true",
+                Some("    This is the same command as the or operator, see __or__ for details."),
+                vec![],
+            )?;
+
+            env.declare_command(
+                "negate",
+                not,
+                false,
+                "cond:negate boolean",
+                "Negates a boolean value",
+                None,
+                Known(ValueType::Bool),
+                vec![],
+            )?;
+
+            env.declare_command(
+                "coalesce",
+                coalesce,
+                false,
+                "cond:coalesce value:any... -> any",
+                "Returns the first argument that is not empty",
+                Some("    If all arguments are empty, or there are no arguments, coalesce returns empty."),
+                Unknown,
+                vec![],
+            )?;
+
             Ok(())
         }))?;
     Ok(())
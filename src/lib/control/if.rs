@@ -1,31 +1,82 @@
 use crate::lang::command::Command;
-use crate::lang::errors::CrushResult;
+use crate::lang::errors::{argument_error_legacy, CrushResult};
 use crate::lang::execution_context::CommandContext;
+use crate::lang::pipe::{empty_channel, pipe};
+use crate::lang::value::Value;
 use signature::signature;
 
 #[signature(
     r#if,
     condition = true,
     short = "Conditionally execute a command once.",
-    example = "if a > 10 {echo \"big\"} {echo \"small\"}"
+    long = "Any number of additional conditions and commands may follow the first command. The command",
+    long = "following the first true condition is invoked. If there is an odd number of additional",
+    long = "arguments, the last one is the command to invoke if no condition is true.",
+    long = "",
+    long = "Conditions may be booleans or commands that return a boolean. A command condition is only",
+    long = "invoked if all previous conditions were false.",
+    example = "if a > 10 {echo \"big\"} {echo \"small\"}",
+    example = "if a > 10 {echo \"big\"} {a > 5} {echo \"medium\"} {echo \"small\"}"
 )]
 pub struct If {
     #[description("the condition to filter on.")]
-    condition: bool,
+    condition: Value,
     #[description("the command to invoke if the condition is true.")]
     true_clause: Command,
-    #[description("the (optional) command to invoke if the condition is false.")]
-    false_clause: Option<Command>,
+    #[unnamed()]
+    #[description("additional conditions and commands, followed by an optional command to invoke if no condition is true.")]
+    remaining: Vec<Value>,
+}
+
+fn evaluate(condition: Value, context: &CommandContext) -> CrushResult<bool> {
+    match condition {
+        Value::Bool(b) => Ok(b),
+        Value::Command(c) => {
+            let (sender, receiver) = pipe();
+            c.invoke(CommandContext {
+                input: empty_channel(),
+                output: sender,
+                arguments: vec![],
+                scope: context.scope.clone(),
+                this: None,
+                global_state: context.global_state.clone(),
+            })?;
+            match receiver.recv()? {
+                Value::Bool(b) => Ok(b),
+                v => argument_error_legacy(format!(
+                    "Expected condition to return a boolean, got a {}", v.value_type())),
+            }
+        }
+        v => argument_error_legacy(format!(
+            "Expected condition to be a boolean or a command, got a {}", v.value_type())),
+    }
+}
+
+fn as_command(value: Value) -> CrushResult<Command> {
+    match value {
+        Value::Command(c) => Ok(c),
+        v => argument_error_legacy(format!("Expected a command, got a {}", v.value_type())),
+    }
 }
 
 fn r#if(context: CommandContext) -> CrushResult<()> {
     let cfg: If = If::parse(context.arguments.clone(), &context.global_state.printer())?;
 
-    if cfg.condition {
-        cfg.true_clause.invoke(context.with_args(vec![], None))
-    } else {
-        cfg.false_clause
-            .map(|v| v.invoke(context.with_args(vec![], None)))
-            .unwrap_or(Ok(()))
+    if evaluate(cfg.condition, &context)? {
+        return cfg.true_clause.invoke(context.with_args(vec![], None));
+    }
+
+    let mut remaining = cfg.remaining.into_iter();
+    while let Some(condition) = remaining.next() {
+        match remaining.next() {
+            Some(clause) => {
+                let clause = as_command(clause)?;
+                if evaluate(condition, &context)? {
+                    return clause.invoke(context.with_args(vec![], None));
+                }
+            }
+            None => return as_command(condition)?.invoke(context.with_args(vec![], None)),
+        }
     }
+    context.output.send(Value::Empty())
 }
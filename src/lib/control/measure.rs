@@ -0,0 +1,78 @@
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::Unknown;
+use crate::lang::data::r#struct::Struct;
+use crate::lang::errors::{to_crush_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::pipe::pipe;
+use crate::lang::value::Value;
+use chrono::Local;
+use crossbeam::bounded;
+use signature::signature;
+
+#[signature(
+    measure,
+    can_block = true,
+    output = Unknown,
+    short = "Run a command and measure how long it takes.",
+    long = "The output of the body is passed on, and once the body has finished, the elapsed wall clock",
+    long = "time is printed. With --as_value, nothing is printed, and the output is instead a struct with",
+    long = "the fields result, holding the fully read output of the body, and duration.",
+    long = "If the body fails, the error is returned, prefixed with the time elapsed before the failure.",
+    example = "measure {find /usr | count}",
+    example = "(measure --as_value {seq 1_000_000 | sum}):duration"
+)]
+pub struct Measure {
+    #[description("the command to run.")]
+    body: Command,
+    #[default(false)]
+    #[description("return the result and the duration as a struct instead of printing the duration.")]
+    as_value: bool,
+}
+
+fn measure(context: CommandContext) -> CrushResult<()> {
+    let cfg: Measure = Measure::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let (done_send, done_recv) = bounded(1);
+    let (sender, receiver) = pipe();
+
+    let body = cfg.body;
+    let body_context = CommandContext {
+        input: context.input.clone(),
+        output: if cfg.as_value { sender } else { context.output.clone() },
+        arguments: vec![],
+        scope: context.scope.clone(),
+        this: None,
+        global_state: context.global_state.clone(),
+    };
+
+    let start = Local::now();
+    context.global_state.threads().spawn(
+        "measure",
+        move || {
+            let _ = done_send.send(body.invoke(body_context));
+            Ok(())
+        })?;
+
+    let result = if cfg.as_value {
+        receiver.recv().and_then(|v| v.materialize())
+    } else {
+        Ok(Value::Empty())
+    };
+    let status = to_crush_error(done_recv.recv())?;
+    let duration = Local::now() - start;
+
+    if let Err(e) = status {
+        return Err(e.with_prefix(&format!("Failed after {}", Value::Duration(duration))));
+    }
+
+    if cfg.as_value {
+        context.output.send(Value::Struct(Struct::new(
+            vec![
+                ("result", result?),
+                ("duration", Value::Duration(duration)),
+            ],
+            None)))
+    } else {
+        context.global_state.printer().line(&format!("Elapsed time: {}", Value::Duration(duration)));
+        Ok(())
+    }
+}
@@ -7,8 +7,13 @@ use crate::lang::{
 use signature::signature;
 use std::env;
 
-use crate::lang::command::OutputType::Known;
-use chrono::Duration;
+use crate::lang::command::{Command, CrushCommand};
+use crate::lang::pipe::{pipe, black_hole, empty_channel};
+use crate::lang::execution_context::JobContext;
+use crate::lang::files::Files;
+use std::convert::TryFrom;
+use crate::lang::command::OutputType::{Known, Passthrough, Unknown};
+use std::cmp::min;
 use std::path::PathBuf;
 use crate::lang::data::table::{ColumnType, Row};
 use std::io::{Write, Read};
@@ -20,7 +25,10 @@ use os_pipe::PipeReader;
 mod r#for;
 mod r#if;
 mod r#loop;
+mod measure;
 mod sudo;
+mod retry;
+mod timeout;
 mod timer;
 mod r#while;
 
@@ -48,6 +56,30 @@ fn r#continue(context: CommandContext) -> CrushResult<()> {
     context.output.empty()
 }
 
+fn bind(mut context: CommandContext) -> CrushResult<()> {
+    if context.arguments.is_empty() {
+        return argument_error_legacy("No command given");
+    }
+    let command = context.arguments.remove(0);
+    match (command.argument_type, command.value) {
+        (None, Value::Command(c)) => context
+            .output
+            .send(Value::Command(<dyn CrushCommand>::partial(c, context.arguments))),
+        _ => argument_error_legacy("Expected first argument to be a command"),
+    }
+}
+
+fn apply(mut context: CommandContext) -> CrushResult<()> {
+    if context.arguments.is_empty() {
+        return argument_error_legacy("No command given");
+    }
+    let command = context.arguments.remove(0);
+    match (command.argument_type, command.value) {
+        (None, Value::Command(c)) => c.invoke(context),
+        _ => argument_error_legacy("Expected first argument to be a command"),
+    }
+}
+
 fn cmd(mut context: CommandContext) -> CrushResult<()> {
     if context.arguments.is_empty() {
         return argument_error_legacy("No command given");
@@ -150,36 +182,109 @@ impl BinaryReader for PipeReader {
     }
 }
 
+#[signature(
+compose,
+can_block = false,
+output = Known(ValueType::Command),
+short = "Create a new command that pipes the output of one command into another",
+long = "The arguments given when invoking the new command are passed to the first command.",
+example = "top_cpu := (compose {ps} {sort cpu | tail 3})",
+)]
+struct Compose {
+    #[description("the command whose output is used as input.")]
+    first: Command,
+    #[description("the command that receives the output of first.")]
+    second: Command,
+}
+
+fn compose(context: CommandContext) -> CrushResult<()> {
+    let cfg: Compose = Compose::parse(context.arguments, &context.global_state.printer())?;
+    context.output.send(Value::Command(<dyn CrushCommand>::compose(cfg.first, cfg.second)))
+}
+
 #[signature(
 sleep,
 can_block = true,
+output = Passthrough,
 short = "Pause execution of commands for the specified amount of time",
-long = "    Execute the specified command all specified hosts"
+long = "The input of the command is passed on unchanged once the time has passed, so that sleep",
+long = "can be used in the middle of a pipeline. The sleep is cut short if the surrounding scope",
+long = "is stopped, e.g. by a break command or a timeout.",
+example = "sleep (duration:of seconds=2)",
+example = "sleep 500",
 )]
 struct Sleep {
-    #[description("the time to sleep for.")]
-    duration: Duration,
+    #[description("the time to sleep for, either a duration or an integer number of milliseconds.")]
+    duration: Value,
 }
 
+/// The longest we sleep in one go before checking if the scope has been stopped.
+const SLEEP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
 fn sleep(context: CommandContext) -> CrushResult<()> {
     let cfg = Sleep::parse(context.arguments, &context.global_state.printer())?;
-    std::thread::sleep(to_crush_error(cfg.duration.to_std())?);
-    context.output.send(Value::Empty())?;
-    Ok(())
+    let duration = match cfg.duration {
+        Value::Duration(d) => to_crush_error(d.to_std())?,
+        Value::Integer(ms) if ms >= 0 => std::time::Duration::from_millis(ms as u64),
+        v => return argument_error_legacy(format!(
+            "Expected a non-negative duration or integer, got a value of type {}",
+            v.value_type())),
+    };
+    let deadline = std::time::Instant::now() + duration;
+    loop {
+        let now = std::time::Instant::now();
+        if now >= deadline || context.scope.is_stopped() {
+            break;
+        }
+        std::thread::sleep(min(deadline - now, SLEEP_CHECK_INTERVAL));
+    }
+    context.output.send(context.input.recv()?)
 }
 
 #[signature(
 bg,
 short = "Run a pipeline in background",
-example = "pipe := ((table_input_stream value=integer):pipe)\n    _1 := (seq 100_000 | pipe:output:write | bg)\n    sum_job_id := (pipe:input | sum | bg)\n    pipe:close\n    sum_job_id | fg"
+long = "When used at the end of a pipeline, the pipeline is run in the background. When a body is",
+long = "given, the body is run in the background in a child scope, with the input of bg as its",
+long = "input. In both cases, a handle is returned immediately, and the output can be retrieved",
+long = "by passing the handle to fg, which waits for it to become available.",
+example = "pipe := ((table_input_stream value=integer):pipe)\n    _1 := (seq 100_000 | pipe:output:write | bg)\n    sum_job_id := (pipe:input | sum | bg)\n    pipe:close\n    sum_job_id | fg",
+example = "job := (bg {seq 1_000 | sum})\n    job | fg"
 )]
-struct Bg {}
+struct Bg {
+    #[description("the command to run in the background.")]
+    body: Option<Command>,
+}
 
 fn bg(context: CommandContext) -> CrushResult<()> {
+    let cfg: Bg = Bg::parse(context.arguments, &context.global_state.printer())?;
     let output = context.output.initialize(
         vec![ColumnType::new("value", ValueType::Any)])?;
-    if let Ok(value) = context.input.recv() {
-        output.send(Row::new(vec![value]))?;
+    match cfg.body {
+        None => {
+            if let Ok(value) = context.input.recv() {
+                output.send(Row::new(vec![value]))?;
+            }
+        }
+        Some(body) => {
+            let (sender, receiver) = pipe();
+            let body_context = CommandContext {
+                input: context.input,
+                output: sender,
+                arguments: vec![],
+                scope: context.scope.create_child(&context.scope, false),
+                this: None,
+                global_state: context.global_state.clone(),
+            };
+            let threads = context.global_state.threads();
+            threads.spawn("bg", move || body.invoke(body_context))?;
+            threads.spawn("bg:output", move || {
+                if let Ok(value) = receiver.recv() {
+                    output.send(Row::new(vec![value]))?;
+                }
+                Ok(())
+            })?;
+        }
     }
     Ok(())
 }
@@ -201,6 +306,94 @@ fn fg(context: CommandContext) -> CrushResult<()> {
     }
 }
 
+#[signature(
+source,
+can_block = true,
+output = Unknown,
+short = "Execute the commands in a file in the current scope",
+long = "Unlike a closure, the commands are not run in a child scope, so any variables or commands",
+long = "declared by the file remain available afterwards. The output of the last job of the file",
+long = "is used as the output of source.",
+example = "source ./config.crush",
+)]
+struct Source {
+    #[description("the file to execute.")]
+    file: Files,
+}
+
+/// Parse the code and run the resulting jobs in the specified scope, one at a time. The output of
+/// the last job is sent to the output of the context, the output of all other jobs is discarded.
+/// Errors are prefixed with the name of the code, e.g. the file it was read from.
+fn run_code(code: &str, name: &str, scope: Scope, context: CommandContext) -> CrushResult<()> {
+    let jobs = context.global_state.parser().parse(code, &scope)
+        .map_err(|e| e.with_definition(code).with_prefix(name))?;
+
+    if jobs.is_empty() {
+        return context.output.send(Value::Empty());
+    }
+
+    let last = jobs.len() - 1;
+    for (idx, job) in jobs.iter().enumerate() {
+        let output = if idx == last {
+            context.output.clone()
+        } else {
+            black_hole()
+        };
+        let handle = job.invoke(JobContext::new(
+            empty_channel(),
+            output,
+            scope.clone(),
+            context.global_state.clone(),
+        )).map_err(|e| e.with_definition(code).with_prefix(name))?;
+
+        if let Some(id) = handle {
+            context.global_state.threads().join_one(
+                id,
+                &context.global_state.printer().with_source(code, job.location()));
+        }
+    }
+    Ok(())
+}
+
+fn source(context: CommandContext) -> CrushResult<()> {
+    let cfg: Source = Source::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let file = PathBuf::try_from(cfg.file)?;
+    let name = file.to_string_lossy().to_string();
+    let code = to_crush_error(std::fs::read_to_string(&file))
+        .map_err(|e| e.with_prefix(&name))?;
+    let scope = context.scope.clone();
+    run_code(&code, &name, scope, context)
+}
+
+#[signature(
+eval,
+can_block = true,
+output = Unknown,
+short = "Execute a string as crush code",
+long = "The code is run in a child scope of the current scope, with the same privileges as any other",
+long = "code in the current scope, so only pass trusted strings to eval. The output of the last job",
+long = "of the code is used as the output of eval.",
+long = "With --sandbox, the child scope is read only, so the code can't declare any new variables.",
+long = "Do note that this does not stop the code from modifying existing variables or the file system.",
+example = "eval \"seq 10 | sum\"",
+)]
+struct Eval {
+    #[description("the code to execute.")]
+    code: String,
+    #[default(false)]
+    #[description("run the code in a read only scope.")]
+    sandbox: bool,
+}
+
+fn eval(context: CommandContext) -> CrushResult<()> {
+    let cfg: Eval = Eval::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let scope = context.scope.create_child(&context.scope, false);
+    if cfg.sandbox {
+        scope.readonly();
+    }
+    run_code(&cfg.code, "eval", scope, context)
+}
+
 pub fn declare(root: &Scope) -> CrushResult<()> {
     let e = root.create_namespace(
         "control",
@@ -235,6 +428,44 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
                 vec![],
             )?;
 
+            env.declare_command(
+                "bind",
+                bind,
+                false,
+                "bind command:command @arguments:any",
+                "Create a new command with some of the arguments of command already supplied",
+                Some(
+                    r#"    The bound arguments, named or unnamed, are passed before any arguments given
+    when invoking the new command.
+
+    Example:
+
+    greet := (bind {|greeting name| echo ("{} {}":format greeting name)} "Hello")
+    greet "world""#,
+                ),
+                Known(ValueType::Command),
+                vec![],
+            )?;
+
+            env.declare_command(
+                "apply",
+                apply,
+                true,
+                "apply command:command @arguments:any",
+                "Invoke a command value with the specified arguments",
+                Some(
+                    r#"    The input and output of apply are passed on to the command.
+
+    Example:
+
+    double := (bind math:mul 2)
+    apply double 21
+    seq 5 | apply (compose {head 2} {sum})"#,
+                ),
+                Unknown,
+                vec![],
+            )?;
+
             env.declare_command(
                 "cmd",
                 cmd,
@@ -247,10 +478,16 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
             )?;
             Break::declare(env)?;
             timer::Timer::declare(env)?;
+            timeout::Timeout::declare(env)?;
+            measure::Measure::declare(env)?;
+            retry::Retry::declare(env)?;
             Continue::declare(env)?;
+            Compose::declare(env)?;
             Sleep::declare(env)?;
             Bg::declare(env)?;
             Fg::declare(env)?;
+            Source::declare(env)?;
+            Eval::declare(env)?;
             Ok(())
         }),
     )?;
@@ -0,0 +1,102 @@
+use crate::lang::argument::Argument;
+use crate::lang::ast::Location;
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::Unknown;
+use crate::lang::errors::{argument_error_legacy, error, to_crush_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::pipe::{black_hole, empty_channel, pipe};
+use crate::lang::value::Value;
+use chrono::Duration;
+use crossbeam::bounded;
+use signature::signature;
+
+#[signature(
+    retry,
+    can_block = true,
+    output = Unknown,
+    short = "Run a command, running it again if it fails.",
+    long = "An attempt is considered failed if the command returns an error or does not",
+    long = "produce any output. If every attempt fails, the error from the last attempt is",
+    long = "returned. If an error handler is given, it is called after every failed attempt",
+    long = "with the error message as its only argument.",
+    example = "retry times=5 delay=(duration:of seconds=1) {http \"https://example.com\"}"
+)]
+pub struct Retry {
+    #[description("the command to run.")]
+    body: Command,
+    #[description("the maximum number of times to run the command.")]
+    #[default(3usize)]
+    times: usize,
+    #[description("the time to wait between attempts.")]
+    delay: Option<Duration>,
+    #[description("a command to call with the error message after every failed attempt.")]
+    on_error: Option<Command>,
+}
+
+fn attempt(body: &Command, context: &CommandContext) -> CrushResult<Value> {
+    let (sender, receiver) = pipe();
+    let (done_send, done_recv) = bounded(1);
+    let body = body.copy();
+    let body_context = CommandContext {
+        input: empty_channel(),
+        output: sender,
+        arguments: vec![],
+        scope: context.scope.clone(),
+        this: None,
+        global_state: context.global_state.clone(),
+    };
+    context.global_state.threads().spawn(
+        "retry",
+        move || {
+            let _ = done_send.send(body.invoke(body_context));
+            Ok(())
+        })?;
+
+    // Errors from commands inside a closure are reported directly and do not propagate, so
+    // a body that finishes without producing any output is also considered a failure.
+    match receiver.recv() {
+        Ok(value) => Ok(value),
+        Err(_) => match done_recv.recv() {
+            Ok(Err(e)) => Err(e),
+            _ => error("The command did not produce any output"),
+        },
+    }
+}
+
+fn retry(context: CommandContext) -> CrushResult<()> {
+    let cfg: Retry = Retry::parse(context.arguments.clone(), &context.global_state.printer())?;
+    if cfg.times == 0 {
+        return argument_error_legacy("The number of attempts must be at least one");
+    }
+    let location = context
+        .arguments
+        .get(0)
+        .map(|a| a.location)
+        .unwrap_or(Location::new(0, 0));
+
+    let mut attempts = 1;
+    loop {
+        match attempt(&cfg.body, &context) {
+            Ok(value) => return context.output.send(value),
+            Err(e) => {
+                if let Some(handler) = &cfg.on_error {
+                    handler.invoke(CommandContext {
+                        input: empty_channel(),
+                        output: black_hole(),
+                        arguments: vec![Argument::unnamed(Value::string(e.message()), location)],
+                        scope: context.scope.clone(),
+                        this: None,
+                        global_state: context.global_state.clone(),
+                    })?;
+                }
+                if attempts >= cfg.times {
+                    return Err(e);
+                }
+            }
+        }
+        attempts += 1;
+        if let Some(delay) = &cfg.delay {
+            std::thread::sleep(to_crush_error(delay.to_std())?);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::Unknown;
+use crate::lang::errors::{error, to_crush_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::value::Value;
+use chrono::Duration;
+use crossbeam::bounded;
+use signature::signature;
+
+#[signature(
+    timeout,
+    can_block = true,
+    output = Unknown,
+    short = "Run a command, giving up if it has not finished within the specified time.",
+    long = "The body is run on a separate thread, and its output is passed on as it is produced.",
+    long = "If the body has not finished when the deadline expires, it is stopped and an error",
+    long = "is returned. Note that a command that is blocking, e.g. on a network request, is",
+    long = "only stopped once it returns.",
+    example = "timeout (duration:of seconds=5) {http \"https://example.com\"}"
+)]
+pub struct Timeout {
+    #[description("the maximum time to let the body run.")]
+    duration: Duration,
+    #[description("the command to run.")]
+    body: Command,
+}
+
+fn timeout(context: CommandContext) -> CrushResult<()> {
+    let cfg: Timeout = Timeout::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let deadline = to_crush_error(cfg.duration.to_std())?;
+    let env = context.scope.create_child(&context.scope, false);
+    let (done_send, done_recv) = bounded(1);
+
+    let body = cfg.body;
+    let body_context = CommandContext {
+        input: context.input.clone(),
+        output: context.output.clone(),
+        arguments: vec![],
+        scope: env.clone(),
+        this: None,
+        global_state: context.global_state.clone(),
+    };
+    context.global_state.threads().spawn(
+        "timeout",
+        move || {
+            let _ = done_send.send(body.invoke(body_context));
+            Ok(())
+        })?;
+
+    match done_recv.recv_timeout(deadline) {
+        Ok(res) => res,
+        Err(_) => {
+            env.stop();
+            error(format!(
+                "Timed out after {}",
+                Value::Duration(cfg.duration).to_string()
+            ))
+        }
+    }
+}
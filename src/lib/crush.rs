@@ -65,6 +65,41 @@ fn prompt(context: CommandContext) -> CrushResult<()> {
     context.output.send(Value::Empty())
 }
 
+lazy_static! {
+    static ref HISTORY_OUTPUT_TYPE: Vec<ColumnType> = vec![
+        ColumnType::new("idx", ValueType::Integer),
+        ColumnType::new("command", ValueType::String),
+    ];
+}
+
+#[signature(
+history,
+output = Known(ValueType::TableInputStream(HISTORY_OUTPUT_TYPE.clone())),
+short = "The commands previously entered into the interactive shell",
+long = "The history is loaded from and saved to the history file in the crush configuration directory.",
+example = "crush:history | where {command =~ re\"ls.*\"}")]
+struct History {
+    #[default(false)]
+    #[description("clear the history instead of listing it.")]
+    clear: bool,
+}
+
+fn history(context: CommandContext) -> CrushResult<()> {
+    let cfg: History = History::parse(context.arguments, &context.global_state.printer())?;
+    if cfg.clear {
+        context.global_state.set_history(vec![]);
+        return context.output.send(Value::Empty());
+    }
+    let output = context.output.initialize(HISTORY_OUTPUT_TYPE.clone())?;
+    for (idx, command) in context.global_state.history().drain(..).enumerate() {
+        output.send(Row::new(vec![
+            Value::Integer(idx as i128),
+            Value::String(command),
+        ]))?;
+    }
+    Ok(())
+}
+
 mod locale {
     use super::*;
     use num_format::SystemLocale;
@@ -168,6 +203,7 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
             Prompt::declare(crush)?;
             Threads::declare(crush)?;
             Exit::declare(crush)?;
+            History::declare(crush)?;
 
             crush.create_namespace(
                 "locale",
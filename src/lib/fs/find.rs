@@ -31,7 +31,7 @@ lazy_static! {
     ];
 }
 
-fn format_permissions(mode: u32) -> String {
+pub(super) fn format_permissions(mode: u32) -> String {
     let mut res = String::with_capacity(9);
     let sticky = ((mode >> 9) & 1) != 0;
     let setgid = ((mode >> 9) & 2) != 0;
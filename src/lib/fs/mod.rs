@@ -1,9 +1,10 @@
 use crate::lang::command::OutputType::Known;
-use crate::lang::errors::{to_crush_error, CrushResult};
+use crate::lang::errors::{mandate, to_crush_error, CrushResult};
 use crate::lang::execution_context::CommandContext;
 use crate::lang::help::Help;
 use crate::lang::printer::Printer;
 use crate::lang::data::scope::Scope;
+use crate::lang::data::r#struct::Struct;
 use crate::lang::value::Value;
 use crate::lang::value::ValueType;
 use crate::util::file::{cwd, home};
@@ -14,6 +15,7 @@ use std::convert::TryFrom;
 
 mod du;
 mod find;
+mod stat;
 
 #[signature(
 cd,
@@ -113,6 +115,48 @@ members of a value, write "dir <value>".
     }
 }
 
+#[signature(
+which,
+can_block=false,
+output = Known(ValueType::Struct),
+short = "Show what the specified name resolves to.",
+long = "The returned struct describes whether the name refers to a builtin command, a",
+long = "closure or some other variable, as well as its type. For commands, it also",
+long = "includes the help text, and for closures the source code.",
+example = "which \"ls\"",
+)]
+pub struct Which {
+    #[description("the name to look up.")]
+    name: String,
+}
+
+pub fn which(context: CommandContext) -> CrushResult<()> {
+    let cfg: Which = Which::parse(context.arguments, &context.global_state.printer())?;
+    let value = mandate(
+        context.scope.get(&cfg.name)?,
+        format!("Unknown name {}", cfg.name),
+    )?;
+    let (kind, help) = match &value {
+        Value::Command(cmd) if cmd.name() == "closure" => {
+            ("closure", cmd.help().long_help().unwrap_or_default())
+        }
+        Value::Command(cmd) => (
+            "builtin",
+            format!("{}\n\n    {}", cmd.help().signature(), cmd.help().short_help()),
+        ),
+        _ => ("variable", "".to_string()),
+    };
+    context.output.send(Value::Struct(Struct::new(
+        vec![
+            ("name", Value::String(cfg.name)),
+            ("kind", Value::string(kind)),
+            ("type", Value::Type(value.value_type())),
+            ("help", Value::String(help)),
+        ],
+        None,
+    )))
+}
+
 pub fn declare(root: &Scope) -> CrushResult<()> {
     let e = root.create_namespace(
         "fs",
@@ -122,7 +166,9 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
             Cd::declare(env)?;
             Pwd::declare(env)?;
             HelpSignature::declare(env)?;
+            Which::declare(env)?;
             du::Du::declare(env)?;
+            stat::Stat::declare(env)?;
             Ok(())
         }),
     )?;
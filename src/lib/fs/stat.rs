@@ -0,0 +1,91 @@
+use signature::signature;
+use crate::lang::files::Files;
+use crate::lang::execution_context::CommandContext;
+use crate::lang::errors::{io_error, CrushResult};
+use std::path::PathBuf;
+use crate::lang::data::r#struct::Struct;
+use crate::lang::data::table::Row;
+use crate::lang::value::Value;
+use crate::lang::value::ValueType;
+use crate::lang::data::table::ColumnType;
+use lazy_static::lazy_static;
+use chrono::{DateTime, Local};
+use std::fs::{metadata, symlink_metadata};
+use std::os::unix::fs::PermissionsExt;
+use super::find::format_permissions;
+
+lazy_static! {
+    static ref OUTPUT_TYPE: Vec<ColumnType> = vec![
+        ColumnType::new("file", ValueType::File),
+        ColumnType::new("size", ValueType::Integer),
+        ColumnType::new("is_dir", ValueType::Bool),
+        ColumnType::new("is_symlink", ValueType::Bool),
+        ColumnType::new("modified", ValueType::Time),
+        ColumnType::new("permissions", ValueType::String),
+    ];
+}
+
+#[signature(
+stat,
+can_block = true,
+short = "Return metadata about files.",
+long = "If a single file is given as an argument, a struct is returned. If several files are given,",
+long = "or the files are read from a table stream on input, a table stream with one row per file",
+long = "is returned. In both cases, the following fields are present:",
+long = "* file:file the file",
+long = "* size:integer the size of the file in bytes",
+long = "* is_dir:bool is the file a directory",
+long = "* is_symlink:bool is the file a symbolic link",
+long = "* modified:time the time the file was last modified",
+long = "* permissions:string the permission bits of the file",
+example = "stat ./Cargo.toml",
+example = "find . | select ^file | stat --follow",
+)]
+pub struct Stat {
+    #[unnamed()]
+    #[description("the files to examine.")]
+    files: Files,
+    #[description("follow symbolic links and describe the file they point to.")]
+    #[default(false)]
+    follow: bool,
+}
+
+fn stat_row(file: PathBuf, follow: bool) -> CrushResult<Vec<Value>> {
+    let meta = match if follow { metadata(&file) } else { symlink_metadata(&file) } {
+        Ok(m) => m,
+        Err(e) => return io_error(format!("Could not stat file {}: {}", file.display(), e)),
+    };
+    let modified: DateTime<Local> = match meta.modified() {
+        Ok(m) => DateTime::from(m),
+        Err(e) => return io_error(format!("Could not stat file {}: {}", file.display(), e)),
+    };
+    Ok(vec![
+        Value::File(file),
+        Value::Integer(i128::from(meta.len())),
+        Value::Bool(meta.is_dir()),
+        Value::Bool(meta.file_type().is_symlink()),
+        Value::Time(modified),
+        Value::String(format_permissions(meta.permissions().mode())),
+    ])
+}
+
+fn stat(context: CommandContext) -> CrushResult<()> {
+    let mut cfg: Stat = Stat::parse(context.arguments, &context.global_state.printer())?;
+    let single = cfg.files.had_entries();
+    if !single {
+        cfg.files.expand(context.input.recv()?, &context.global_state.printer())?;
+    }
+    let mut files = Vec::from(cfg.files);
+    if single && files.len() == 1 {
+        context.output.send(Value::Struct(Struct::from_vec(
+            stat_row(files.remove(0), cfg.follow)?,
+            OUTPUT_TYPE.clone(),
+        )))
+    } else {
+        let output = context.output.initialize(OUTPUT_TYPE.clone())?;
+        for file in files {
+            output.send(Row::new(stat_row(file, cfg.follow)?))?;
+        }
+        Ok(())
+    }
+}
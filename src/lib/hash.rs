@@ -0,0 +1,115 @@
+use crate::lang::command::OutputType::Known;
+use crate::lang::errors::{argument_error_legacy, to_crush_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::scope::Scope;
+use crate::lang::value::{Value, ValueType};
+use crate::util::hex::to_hex;
+use sha2::Digest;
+use signature::signature;
+use std::fs::File;
+use std::io::Read;
+
+fn update_from_reader<D: Digest, R: Read + ?Sized>(
+    digest: &mut D,
+    reader: &mut R,
+) -> CrushResult<()> {
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = to_crush_error(reader.read(&mut buf))?;
+        if n == 0 {
+            return Ok(());
+        }
+        digest.input(&buf[..n]);
+    }
+}
+
+/// Feed the specified value, or the input if no value was given, to the digest.
+fn run<D: Digest>(
+    value: Option<Value>,
+    context: CommandContext,
+    mut digest: D,
+) -> CrushResult<()> {
+    let value = match value {
+        Some(v) => v,
+        None => context.input.recv()?,
+    };
+    match value {
+        Value::String(s) => digest.input(s.as_bytes()),
+        Value::Binary(b) => digest.input(&b),
+        Value::File(f) => update_from_reader(&mut digest, &mut to_crush_error(File::open(&f))?)?,
+        Value::BinaryInputStream(mut input) => update_from_reader(&mut digest, input.as_mut())?,
+        v => match v.stream() {
+            Some(mut input) => {
+                while let Ok(row) = input.read() {
+                    let line = Vec::from(row)
+                        .iter()
+                        .map(|c| c.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\t");
+                    digest.input(line.as_bytes());
+                    digest.input(b"\n");
+                }
+            }
+            None => {
+                return argument_error_legacy(format!(
+                    "Can't calculate the digest of a value of type {}",
+                    v.value_type().to_string()
+                ))
+            }
+        },
+    }
+    context.output.send(Value::String(
+        digest.result().iter().map(|b| to_hex(*b)).collect(),
+    ))
+}
+
+#[signature(
+    md5,
+    can_block = true,
+    output = Known(ValueType::String),
+    short = "Calculate the md5 digest of a file, binary, string or stream",
+    long = "If no value is given, the digest of the input is calculated. Files are read",
+    long = "incrementally. The rows of table streams are converted to tab separated lines.",
+    example = "hash:md5 ./Cargo.toml"
+)]
+struct Md5 {
+    #[description("the value to calculate the digest of.")]
+    value: Option<Value>,
+}
+
+fn md5(context: CommandContext) -> CrushResult<()> {
+    let cfg: Md5 = Md5::parse(context.arguments.clone(), &context.global_state.printer())?;
+    run(cfg.value, context, md5::Md5::new())
+}
+
+#[signature(
+    sha256,
+    can_block = true,
+    output = Known(ValueType::String),
+    short = "Calculate the sha256 digest of a file, binary, string or stream",
+    long = "If no value is given, the digest of the input is calculated. Files are read",
+    long = "incrementally. The rows of table streams are converted to tab separated lines.",
+    example = "hash:sha256 ./Cargo.toml"
+)]
+struct Sha256 {
+    #[description("the value to calculate the digest of.")]
+    value: Option<Value>,
+}
+
+fn sha256(context: CommandContext) -> CrushResult<()> {
+    let cfg: Sha256 = Sha256::parse(context.arguments.clone(), &context.global_state.printer())?;
+    run(cfg.value, context, sha2::Sha256::new())
+}
+
+pub fn declare(root: &Scope) -> CrushResult<()> {
+    root.create_namespace(
+        "hash",
+        "Message digests",
+        Box::new(move |env| {
+            Md5::declare(env)?;
+            Sha256::declare(env)?;
+            Ok(())
+        }),
+    )?;
+    Ok(())
+}
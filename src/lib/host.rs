@@ -1,4 +1,4 @@
-use crate::lang::errors::{to_crush_error, CrushResult};
+use crate::lang::errors::{argument_error_legacy, to_crush_error, CrushResult};
 use crate::lang::execution_context::CommandContext;
 use crate::lang::data::r#struct::Struct;
 use crate::lang::data::scope::Scope;
@@ -10,7 +10,7 @@ use sys_info;
 use lazy_static::lazy_static;
 use battery::State;
 use chrono::Duration;
-use crate::lang::command::OutputType::Known;
+use crate::lang::command::OutputType::{Known, Unknown};
 
 #[signature(name, can_block = false, short = "name of this host")]
 struct Name {}
@@ -21,6 +21,76 @@ fn name(context: CommandContext) -> CrushResult<()> {
         .send(Value::String(to_crush_error(sys_info::hostname())?))
 }
 
+lazy_static! {
+    static ref ENV_OUTPUT_TYPE: Vec<ColumnType> = vec![
+        ColumnType::new("name", ValueType::String),
+        ColumnType::new("value", ValueType::String),
+    ];
+}
+
+#[signature(
+env,
+can_block = true,
+output = Unknown,
+short = "The environment variables of the Crush process",
+long = "If a name is given, the value of that environment variable is returned, or the empty",
+long = "value if it is unset. Otherwise, a table stream of all environment variables is returned.",
+long = "",
+long = "Note that environment variables are not Crush variables. Use var:env to list the",
+long = "Crush variables that are in scope.",
+example = "host:env \"PATH\"")]
+struct Env {
+    #[description("the name of the environment variable to return.")]
+    name: Option<String>,
+}
+
+fn env(context: CommandContext) -> CrushResult<()> {
+    let cfg: Env = Env::parse(context.arguments, &context.global_state.printer())?;
+    match cfg.name {
+        Some(name) => context.output.send(
+            std::env::var_os(name)
+                .map(|v| Value::String(v.to_string_lossy().to_string()))
+                .unwrap_or(Value::Empty())),
+        None => {
+            let output = context.output.initialize(ENV_OUTPUT_TYPE.clone())?;
+            let mut vars = std::env::vars_os()
+                .map(|(k, v)| (k.to_string_lossy().to_string(), v.to_string_lossy().to_string()))
+                .collect::<Vec<_>>();
+            vars.sort();
+            for (name, value) in vars {
+                output.send(Row::new(vec![Value::String(name), Value::String(value)]))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[signature(
+setenv,
+can_block = false,
+output = Known(ValueType::Empty),
+short = "Set an environment variable of the Crush process",
+long = "The variable will be visible to all external commands started afterwards.",
+example = "host:setenv \"EDITOR\" \"emacs\"")]
+struct SetEnv {
+    #[description("the name of the environment variable.")]
+    name: String,
+    #[description("the new value.")]
+    value: String,
+}
+
+fn setenv(context: CommandContext) -> CrushResult<()> {
+    let cfg: SetEnv = SetEnv::parse(context.arguments, &context.global_state.printer())?;
+    if cfg.name.is_empty() || cfg.name.contains(|c: char| c == '=' || c == '\0') {
+        return argument_error_legacy(format!("Invalid environment variable name {}", cfg.name));
+    }
+    if cfg.value.contains('\0') {
+        return argument_error_legacy("Environment variable values may not contain null characters");
+    }
+    std::env::set_var(cfg.name, cfg.value);
+    context.output.send(Value::Empty())
+}
+
 lazy_static! {
     static ref BATTERY_OUTPUT_TYPE: Vec<ColumnType> = vec![
         ColumnType::new("vendor", ValueType::String),
@@ -169,8 +239,10 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
         "Metadata about this host",
         Box::new(move |host| {
             Battery::declare(host)?;
+            Env::declare(host)?;
             Mem::declare(host)?;
             Name::declare(host)?;
+            SetEnv::declare(host)?;
             host.create_namespace(
                 "os",
                 "Metadata about the operating system this host is running",
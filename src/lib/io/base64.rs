@@ -0,0 +1,81 @@
+use crate::lang::command::OutputType::Known;
+use crate::lang::errors::{argument_error_legacy, data_error, to_crush_error, CrushResult};
+use crate::lang::data::scope::ScopeLoader;
+use crate::lang::value::ValueType;
+use crate::lang::{execution_context::CommandContext, value::Value};
+use signature::signature;
+use std::io::Read;
+
+#[signature(
+    encode,
+    can_block = true,
+    output = Known(ValueType::String),
+    short = "Encode binary data or a string as base64",
+    long = "If no value is given, the input is encoded instead.",
+    example = "base64:encode \"hello\""
+)]
+struct Encode {
+    #[description("the binary or string to encode.")]
+    value: Option<Value>,
+}
+
+fn encode(context: CommandContext) -> CrushResult<()> {
+    let cfg: Encode = Encode::parse(context.arguments, &context.global_state.printer())?;
+    let value = match cfg.value {
+        Some(v) => v,
+        None => context.input.recv()?,
+    };
+    let data = match value {
+        Value::Binary(b) => b,
+        Value::String(s) => s.into_bytes(),
+        Value::BinaryInputStream(mut input) => {
+            let mut buf = Vec::new();
+            to_crush_error(input.read_to_end(&mut buf))?;
+            buf
+        }
+        v => {
+            return argument_error_legacy(format!(
+                "Expected a binary or a string, got a {}",
+                v.value_type().to_string()
+            ))
+        }
+    };
+    context.output.send(Value::String(::base64::encode(&data)))
+}
+
+#[signature(
+    decode,
+    can_block = false,
+    output = Known(ValueType::Binary),
+    short = "Decode a base64 encoded string into binary data",
+    example = "base64:decode \"aGVsbG8=\""
+)]
+struct Decode {
+    #[description("the string to decode.")]
+    value: String,
+}
+
+fn decode(context: CommandContext) -> CrushResult<()> {
+    let cfg: Decode = Decode::parse(context.arguments, &context.global_state.printer())?;
+    let data = cfg.value
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    match ::base64::decode(&data) {
+        Ok(decoded) => context.output.send(Value::Binary(decoded)),
+        Err(e) => data_error(format!("Invalid base64 string: {}", e)),
+    }
+}
+
+pub fn declare(root: &mut ScopeLoader) -> CrushResult<()> {
+    root.create_namespace(
+        "base64",
+        "Base64 encoding and decoding",
+        Box::new(move |env| {
+            Encode::declare(env)?;
+            Decode::declare(env)?;
+            Ok(())
+        }),
+    )?;
+    Ok(())
+}
@@ -5,7 +5,7 @@ use crate::{
 };
 use std::{io::prelude::*, io::BufReader};
 
-use crate::lang::errors::{error, to_crush_error, CrushResult};
+use crate::lang::errors::{argument_error_legacy, data_error, error, to_crush_error, CrushResult};
 use crate::lang::data::table::ColumnType;
 
 use crate::lang::files::Files;
@@ -17,7 +17,13 @@ use signature::signature;
 #[signature(
     from,
     example = "csv:from separator=\",\" head=1 name=string age=integer nick=string",
-    short = "Parse specified files as CSV files"
+    example = "csv:from some_file.csv --infer",
+    short = "Parse specified files as CSV files",
+    long = "If no columns are specified, the first line of the file is used as the column names",
+    long = "and all columns are of type string, unless the infer flag is given.",
+    long = "",
+    long = "Fields may be quoted using double quotes. Quoted fields may contain the separator,",
+    long = "newlines and escaped (doubled) double quotes.",
 )]
 #[derive(Debug)]
 struct From {
@@ -29,80 +35,288 @@ struct From {
     #[named()]
     #[description("name and type of all columns.")]
     columns: OrderedStringMap<ValueType>,
-    #[description("column separator.")]
-    #[default(',')]
-    separator: char,
+    #[description("column separator. If unspecified, it is guessed from the first line of input.")]
+    separator: Option<char>,
     #[default(0usize)]
     #[description("skip this many lines of inpit from the beginning.")]
     head: usize,
     #[description("trim this character from start and end of every value.")]
     trim: Option<char>,
+    #[description("do not treat the first line as column names.")]
+    #[default(false)]
+    no_header: bool,
+    #[description("convert columns where every value is an integer, float or boolean into that type.")]
+    #[default(false)]
+    infer: bool,
+}
+
+/// Guess the separator by looking at the buffered part of the first line of input.
+fn detect_separator(reader: &mut impl BufRead) -> CrushResult<char> {
+    let buf = to_crush_error(reader.fill_buf())?;
+    let first_line = buf.split(|b| *b == b'\n').next().unwrap_or(&[]);
+    let mut best = (',', 0usize);
+    for candidate in &[',', ';', '\t', '|'] {
+        let count = first_line.iter().filter(|b| **b == *candidate as u8).count();
+        if count > best.1 {
+            best = (*candidate, count);
+        }
+    }
+    Ok(best.0)
+}
+
+fn push_field(fields: &mut Vec<String>, field: &mut String, trim: Option<char>) {
+    fields.push(match trim {
+        Some(c) => field.trim_matches(c).to_string(),
+        None => field.clone(),
+    });
+    field.clear();
+}
+
+/// Read a single record from the input. A record is usually a single line, but
+/// may span multiple lines if a quoted field contains newlines.
+fn read_record(
+    reader: &mut impl BufRead,
+    separator: char,
+    trim: Option<char>,
+) -> CrushResult<Option<Vec<String>>> {
+    let mut line = String::new();
+    if to_crush_error(reader.read_line(&mut line))? == 0 {
+        return Ok(None);
+    }
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    loop {
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if quoted {
+                if c == '"' {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                } else {
+                    field.push(c);
+                }
+            } else if c == '"' {
+                quoted = true;
+            } else if c == separator {
+                push_field(&mut fields, &mut field, trim);
+            } else if c != '\n' && c != '\r' {
+                field.push(c);
+            }
+        }
+        if !quoted {
+            break;
+        }
+        line.clear();
+        if to_crush_error(reader.read_line(&mut line))? == 0 {
+            return data_error("csv: Unterminated quoted field");
+        }
+    }
+    push_field(&mut fields, &mut field, trim);
+    Ok(Some(fields))
+}
+
+fn read_data_record(
+    reader: &mut impl BufRead,
+    separator: char,
+    trim: Option<char>,
+) -> CrushResult<Option<Vec<String>>> {
+    loop {
+        match read_record(reader, separator, trim)? {
+            Some(fields) if fields.len() == 1 && fields[0].is_empty() => {}
+            r => return Ok(r),
+        }
+    }
+}
+
+fn infer_type(records: &[Vec<String>], idx: usize) -> ValueType {
+    if records.is_empty() {
+        ValueType::String
+    } else if records.iter().all(|r| r[idx].parse::<i128>().is_ok()) {
+        ValueType::Integer
+    } else if records.iter().all(|r| r[idx].parse::<f64>().is_ok()) {
+        ValueType::Float
+    } else if records.iter().all(|r| r[idx].parse::<bool>().is_ok()) {
+        ValueType::Bool
+    } else {
+        ValueType::String
+    }
+}
+
+fn to_row(record: &[String], columns: &[ColumnType]) -> CrushResult<Row> {
+    if record.len() != columns.len() {
+        return data_error(format!(
+            "csv: Wrong number of columns in CSV file, expected {}, got {}",
+            columns.len(),
+            record.len()
+        ));
+    }
+    Ok(Row::new(
+        record
+            .iter()
+            .zip(columns.iter())
+            .map(|(s, t)| t.cell_type.parse(s))
+            .collect::<Result<Vec<Value>, CrushError>>()?,
+    ))
 }
 
 fn from(context: CommandContext) -> CrushResult<()> {
     let cfg: From = From::parse(context.arguments, &context.global_state.printer())?;
-    let columns = cfg
-        .columns
-        .iter()
-        .map(|(k, v)| ColumnType::new(k, v.clone()))
-        .collect::<Vec<_>>();
-    let output = context.output.initialize(columns.clone())?;
-
     let mut reader = BufReader::new(cfg.files.reader(context.input)?);
 
-    let separator = cfg.separator;
     let trim = cfg.trim;
-    let skip = cfg.head;
 
     let mut line = String::new();
-    let mut skipped = 0usize;
-    loop {
+    for _ in 0..cfg.head {
         line.clear();
-        to_crush_error(reader.read_line(&mut line))?;
-        if line.is_empty() {
+        if to_crush_error(reader.read_line(&mut line))? == 0 {
             break;
         }
-        if skipped < skip {
-            skipped += 1;
-            continue;
-        }
-        let line_without_newline = &line[0..line.len() - 1];
-        let mut split: Vec<&str> = line_without_newline
-            .split(separator)
-            .map(|s| trim.map(|c| s.trim_matches(c)).unwrap_or(s))
-            .collect();
-
-        if split.len() != columns.len() {
-            return error("csv: Wrong number of columns in CSV file");
-        }
+    }
 
-        if let Some(trim) = trim {
-            split = split.iter().map(|s| s.trim_matches(trim)).collect();
+    let separator = match cfg.separator {
+        Some(s) => s,
+        None => detect_separator(&mut reader)?,
+    };
+
+    let mut columns = cfg
+        .columns
+        .iter()
+        .map(|(k, v)| ColumnType::new(k, v.clone()))
+        .collect::<Vec<_>>();
+
+    let mut first = None;
+    if columns.is_empty() {
+        let record = match read_data_record(&mut reader, separator, trim)? {
+            Some(r) => r,
+            None => return error("csv: No input"),
+        };
+        if cfg.no_header {
+            columns = (1..=record.len())
+                .map(|idx| ColumnType::new(&format!("column{}", idx), ValueType::String))
+                .collect();
+            first = Some(record);
+        } else {
+            columns = record
+                .iter()
+                .map(|name| ColumnType::new(name, ValueType::String))
+                .collect();
         }
+    }
 
-        match split
-            .iter()
-            .zip(columns.iter())
-            .map(|(s, t)| t.cell_type.parse(*s))
-            .collect::<Result<Vec<Value>, CrushError>>()
-        {
-            Ok(cells) => {
-                let _ = output.send(Row::new(cells));
+    if cfg.infer {
+        let mut records = first.into_iter().collect::<Vec<_>>();
+        while let Some(record) = read_data_record(&mut reader, separator, trim)? {
+            if record.len() != columns.len() {
+                return data_error(format!(
+                    "csv: Wrong number of columns in CSV file, expected {}, got {}",
+                    columns.len(),
+                    record.len()
+                ));
             }
-            Err(err) => {
-                return Err(err);
+            records.push(record);
+        }
+        for idx in 0..columns.len() {
+            if !cfg.columns.contains_key(&columns[idx].name) {
+                columns[idx].cell_type = infer_type(&records, idx);
             }
         }
+        let output = context.output.initialize(columns.clone())?;
+        for record in records {
+            output.send(to_row(&record, &columns)?)?;
+        }
+    } else {
+        let output = context.output.initialize(columns.clone())?;
+        if let Some(record) = first {
+            output.send(to_row(&record, &columns)?)?;
+        }
+        while let Some(record) = read_data_record(&mut reader, separator, trim)? {
+            output.send(to_row(&record, &columns)?)?;
+        }
+    }
+    Ok(())
+}
+
+#[signature(
+    to,
+    can_block = true,
+    example = "ps | csv:to ./processes.csv",
+    short = "Serialize a table stream to CSV format",
+    long = "Fields containing the separator, double quotes or newlines are quoted.",
+)]
+struct To {
+    #[unnamed()]
+    #[description("destination. If unspecified, will write a binary_stream to output.")]
+    file: Files,
+    #[description("column separator.")]
+    #[default(',')]
+    separator: char,
+    #[description("do not write the column names as the first line.")]
+    #[default(false)]
+    no_header: bool,
+}
+
+fn escape_field(field: &str, separator: char) -> String {
+    if field.contains(|c: char| c == separator || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
     }
+}
+
+pub(super) fn write_record(
+    out: &mut dyn Write,
+    fields: impl Iterator<Item=String>,
+    separator: char,
+) -> CrushResult<()> {
+    let line = fields
+        .map(|f| escape_field(&f, separator))
+        .collect::<Vec<_>>()
+        .join(&separator.to_string());
+    to_crush_error(out.write_all(line.as_bytes()))?;
+    to_crush_error(out.write_all(b"\n"))?;
     Ok(())
 }
 
+fn to(context: CommandContext) -> CrushResult<()> {
+    let cfg: To = To::parse(context.arguments, &context.global_state.printer())?;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let mut out = cfg.file.writer(context.output)?;
+            let separator = cfg.separator;
+            if !cfg.no_header {
+                write_record(
+                    out.as_mut(),
+                    input.types().iter().map(|t| t.name.clone()),
+                    separator,
+                )?;
+            }
+            while let Ok(row) = input.read() {
+                write_record(
+                    out.as_mut(),
+                    Vec::from(row).iter().map(|v| v.to_string()),
+                    separator,
+                )?;
+            }
+            Ok(())
+        }
+        None => argument_error_legacy("csv: Expected a table stream"),
+    }
+}
+
 pub fn declare(root: &mut ScopeLoader) -> CrushResult<()> {
     root.create_namespace(
         "csv",
         "CSV I/O",
         Box::new(move |env| {
             From::declare(env)?;
+            To::declare(env)?;
             Ok(())
         }),
     )?;
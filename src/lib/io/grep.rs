@@ -0,0 +1,94 @@
+use crate::lang::command::OutputType::Unknown;
+use crate::lang::errors::{argument_error_legacy, data_error, error, to_crush_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::{ColumnType, Row};
+use crate::lang::value::{Value, ValueType};
+use regex::RegexBuilder;
+use signature::signature;
+
+#[signature(
+    grep,
+    can_block = true,
+    output = Unknown,
+    short = "Pass on the lines of the input that match a regular expression",
+    long = "The input must be a stream with a single column of strings, e.g. the output of lines:from.",
+    long = "With --line_numbers, a line_number column holding the (one based) position of the line",
+    long = "in the input is added before the line. With --count, only the number of matching lines",
+    long = "is returned.",
+    example = "lines:from some_file.txt | grep re\"err(or)?\" --ignore_case",
+)]
+pub struct Grep {
+    #[description("the pattern to search for, either a regular expression or a string.")]
+    pattern: Value,
+    #[description("pass on the lines that do not match instead.")]
+    #[default(false)]
+    invert: bool,
+    #[description("ignore case when matching.")]
+    #[default(false)]
+    ignore_case: bool,
+    #[description("return the number of matching lines instead of the lines themselves.")]
+    #[default(false)]
+    count: bool,
+    #[description("add a column with the line number of each line.")]
+    #[default(false)]
+    line_numbers: bool,
+}
+
+pub fn grep(context: CommandContext) -> CrushResult<()> {
+    let cfg: Grep = Grep::parse(context.arguments, &context.global_state.printer())?;
+    let pattern = match cfg.pattern {
+        Value::Regex(pattern, _) => pattern,
+        Value::String(s) => s,
+        v => return argument_error_legacy(format!(
+            "Expected the pattern to be a regular expression or a string, got a {}",
+            v.value_type())),
+    };
+    let re = to_crush_error(RegexBuilder::new(&pattern).case_insensitive(cfg.ignore_case).build())?;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            if input.types().len() != 1 || input.types()[0].cell_type != ValueType::String {
+                return data_error("Expected an input stream containing a single column of type string");
+            }
+
+            let output = if cfg.count {
+                None
+            } else if cfg.line_numbers {
+                let mut types = vec![ColumnType::new("line_number", ValueType::Integer)];
+                types.extend(input.types().iter().cloned());
+                Some(context.output.initialize(types)?)
+            } else {
+                Some(context.output.initialize(input.types().to_vec())?)
+            };
+
+            let mut line_number = 0;
+            let mut matches = 0;
+            while let Ok(row) = input.read() {
+                line_number += 1;
+                let found = match &row.cells()[0] {
+                    Value::String(s) => re.is_match(s),
+                    _ => return data_error("Expected a string"),
+                };
+                if found == cfg.invert {
+                    continue;
+                }
+                matches += 1;
+                match &output {
+                    None => {}
+                    Some(output) if cfg.line_numbers => {
+                        let mut cells = vec![Value::Integer(line_number)];
+                        cells.append(&mut Vec::from(row));
+                        output.send(Row::new(cells))?;
+                    }
+                    Some(output) => output.send(row)?,
+                }
+            }
+
+            match output {
+                None => context.output.send(Value::Integer(matches)),
+                Some(_) => Ok(()),
+            }
+        }
+        None => error("Expected a stream"),
+    }
+}
@@ -1,8 +1,8 @@
-use crate::lang::errors::{argument_error_legacy, to_crush_error, CrushResult};
+use crate::lang::errors::{argument_error_legacy, data_error, error, to_crush_error, CrushResult};
 use crate::lang::execution_context::CommandContext;
 use crate::lang::{
-    data::binary::binary_channel, data::r#struct::Struct, data::table::ColumnType, data::table::Row, data::table::Table,
-    value::Value, value::ValueType,
+    data::binary::binary_channel, data::dict::Dict, data::r#struct::Struct, value::Value,
+    value::ValueType,
 };
 use reqwest::header::HeaderMap;
 use reqwest::{Method, StatusCode};
@@ -28,9 +28,10 @@ http,
 short = "Make a http request",
 long = "Return a struct with the following fields:",
 long = "* status:integer, the http status of the reply",
-long = "* header:list, the http headers of the reply",
+long = "* headers:dict, the http headers of the reply",
 long = "* body:binary_stream, the content of the reply",
 example = "http \"https://example.com/\" header=(\"Authorization: Bearer {}\":format token)",
+example = "http \"https://example.com/\" method=post body=\"hello\" header=\"Content-Type=text/plain\" --fail",
 can_block = true
 )]
 pub struct Http {
@@ -41,8 +42,13 @@ pub struct Http {
     method: String,
     #[description("form content, if any.")]
     form: Option<String>,
-    #[description("HTTP headers, must be on the form \"key:value\".")]
+    #[description("request body, if any.")]
+    body: Option<String>,
+    #[description("HTTP headers, must be on the form \"key:value\" or \"key=value\".")]
     header: Vec<String>,
+    #[description("fail if the reply does not have a 2xx status code.")]
+    #[default(false)]
+    fail: bool,
 }
 
 fn http(context: CommandContext) -> CrushResult<()> {
@@ -53,44 +59,46 @@ fn http(context: CommandContext) -> CrushResult<()> {
     let mut request = client.request(parse_method(&cfg.method)?, cfg.uri.as_str());
 
     for t in cfg.header.iter() {
-        let h = t.splitn(2, ':').collect::<Vec<&str>>();
-        match h.len() {
-            2 => {
-                request = request.header(h[0], h[1].to_string());
+        match t.find(|c: char| c == ':' || c == '=') {
+            Some(idx) => {
+                request = request.header(t[0..idx].trim(), t[idx + 1..].trim().to_string());
             }
-            _ => {
-                return argument_error_legacy("Bad header format");
+            None => {
+                return argument_error_legacy(format!("Bad header format: {}", t));
             }
         }
     }
 
-    if let Some(body) = cfg.form {
-        request = request.body(body)
+    match (cfg.body, cfg.form) {
+        (Some(_), Some(_)) => return argument_error_legacy("Only one of body and form may be specified"),
+        (Some(body), None) | (None, Some(body)) => request = request.body(body),
+        (None, None) => {}
     }
 
-    let mut b = to_crush_error(request.send())?;
+    let mut b = match request.send() {
+        Ok(b) => b,
+        Err(e) => return error(format!("Request to {} failed: {}", cfg.uri, e)),
+    };
 
     let status: StatusCode = b.status();
+    if cfg.fail && !status.is_success() {
+        return data_error(format!("Request to {} failed with status {}", cfg.uri, status));
+    }
     let header_map: &HeaderMap = b.headers();
-    let headers = Table::new(
-        vec![
-            ColumnType::new("name", ValueType::String),
-            ColumnType::new("value", ValueType::String),
-        ],
-        header_map
+    let headers = Dict::new(ValueType::String, ValueType::String);
+    for name in header_map.keys() {
+        let value = header_map
+            .get_all(name)
             .iter()
-            .map(|(n, v)| {
-                Row::new(vec![
-                    Value::string(n.as_str()),
-                    Value::string(v.to_str().unwrap()),
-                ])
-            })
-            .collect(),
-    );
+            .map(|v| String::from_utf8_lossy(v.as_bytes()).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        headers.insert(Value::string(name.as_str()), Value::String(value))?;
+    }
     context.output.send(Value::Struct(Struct::new(
         vec![
             ("status", Value::Integer(status.as_u16() as i128)),
-            ("headers", Value::Table(headers)),
+            ("headers", Value::Dict(headers)),
             ("body", Value::BinaryInputStream(input)),
         ],
         None,
@@ -6,7 +6,7 @@ use crate::{
 use std::io::{BufReader, Write};
 
 use crate::lang::command::OutputType::Unknown;
-use crate::lang::errors::{error, mandate, to_crush_error, CrushResult};
+use crate::lang::errors::{data_error, error, mandate, to_crush_error, CrushResult};
 use crate::lang::files::Files;
 use crate::lang::data::scope::ScopeLoader;
 use crate::lang::data::table::ColumnType;
@@ -14,8 +14,9 @@ use crate::lang::{data::list::List, data::r#struct::Struct, data::table::Table};
 use signature::signature;
 use std::collections::HashSet;
 use std::convert::TryFrom;
+use crate::lang::data::dict::Dict;
 
-fn from_json(json_value: &serde_json::Value) -> CrushResult<Value> {
+pub(super) fn from_json(json_value: &serde_json::Value, as_dict: bool) -> CrushResult<Value> {
     match json_value {
         serde_json::Value::Null => Ok(Value::Empty()),
         serde_json::Value::Bool(b) => Ok(Value::Bool(*b)),
@@ -34,7 +35,7 @@ fn from_json(json_value: &serde_json::Value) -> CrushResult<Value> {
         serde_json::Value::Array(arr) => {
             let mut lst = arr
                 .iter()
-                .map(|v| from_json(v))
+                .map(|v| from_json(v, as_dict))
                 .collect::<CrushResult<Vec<Value>>>()?;
             let types: HashSet<ValueType> = lst.iter().map(|v| v.value_type()).collect();
             let struct_types: HashSet<Vec<ColumnType>> = lst
@@ -69,9 +70,16 @@ fn from_json(json_value: &serde_json::Value) -> CrushResult<Value> {
                 _ => Ok(Value::List(List::new(ValueType::Any, lst))),
             }
         }
+        serde_json::Value::Object(o) if as_dict => {
+            let d = Dict::new(ValueType::String, ValueType::Any);
+            for (k, v) in o.iter() {
+                d.insert(Value::string(k), from_json(v, as_dict)?)?;
+            }
+            Ok(Value::Dict(d))
+        }
         serde_json::Value::Object(o) => Ok(Value::Struct(Struct::new(
             o.iter()
-                .map(|(k, v)| (k.to_string(), from_json(v)))
+                .map(|(k, v)| (k.to_string(), from_json(v, as_dict)))
                 .map(|(k, v)| match v {
                     Ok(vv) => Ok((k, vv)),
                     Err(e) => Err(e),
@@ -82,7 +90,7 @@ fn from_json(json_value: &serde_json::Value) -> CrushResult<Value> {
     }
 }
 
-fn to_json(value: Value) -> CrushResult<serde_json::Value> {
+pub(super) fn to_json(value: Value) -> CrushResult<serde_json::Value> {
     match value.materialize()? {
         Value::File(s) => Ok(serde_json::Value::from(mandate(
             s.to_str(),
@@ -113,6 +121,8 @@ fn to_json(value: Value) -> CrushResult<serde_json::Value> {
 
         Value::Bool(b) => Ok(serde_json::Value::from(b)),
 
+        Value::Empty() => Ok(serde_json::Value::Null),
+
         Value::Float(f) => Ok(serde_json::Value::from(f)),
 
         Value::Struct(s) => {
@@ -142,17 +152,27 @@ from,
 can_block = true,
 output = Unknown,
 short = "Parse json format",
-example = "(http \"https://jsonplaceholder.typicode.com/todos/3\"):body | json:from")]
+long = "Objects are converted into structs, arrays into lists and null into the empty value.",
+long = "An array of objects that all have the same fields is converted into a table.",
+example = "(http \"https://jsonplaceholder.typicode.com/todos/3\"):body | json:from",
+example = "json:from some_file.json as_dict=true")]
 struct From {
     #[unnamed()]
     files: Files,
+    #[description("convert json objects into dicts instead of structs.")]
+    #[default(false)]
+    as_dict: bool,
 }
 
 pub fn from(context: CommandContext) -> CrushResult<()> {
     let cfg: From = From::parse(context.arguments, &context.global_state.printer())?;
     let reader = BufReader::new(cfg.files.reader(context.input)?);
-    let serde_value = to_crush_error(serde_json::from_reader(reader))?;
-    let crush_value = from_json(&serde_value)?;
+    let serde_value = match serde_json::from_reader(reader) {
+        Ok(v) => v,
+        Err(e) => return data_error(format!(
+            "Invalid json on line {}, column {}: {}", e.line(), e.column(), e)),
+    };
+    let crush_value = from_json(&serde_value, cfg.as_dict)?;
     context.output.send(crush_value)
 }
 
@@ -184,6 +204,74 @@ fn to(context: CommandContext) -> CrushResult<()> {
     Ok(())
 }
 
+#[signature(
+path,
+can_block = true,
+output = Unknown,
+short = "Look up a value inside nested structs, lists, tables and dicts",
+long = "The path is a list of segments separated by dots. Segments are looked up as fields of",
+long = "structs and as keys of dicts. Numeric segments are used as indices into lists and tables,",
+long = "and rows of tables are returned as structs. If no value is given, the input is used.",
+example = "json:from config.json | json:path \"servers.0.host\"",
+example = "json:path \"a.b\" value=some_struct default=0")]
+struct Path {
+    #[description("the path to look up.")]
+    path: String,
+    #[description("the value to look the path up in.")]
+    value: Option<Value>,
+    #[description("the value to return if some segment of the path does not exist.")]
+    default: Option<Value>,
+}
+
+fn index(segment: &str) -> CrushResult<usize> {
+    match segment.parse::<usize>() {
+        Ok(idx) => Ok(idx),
+        Err(_) => data_error(format!("Expected a numeric index, got \"{}\"", segment)),
+    }
+}
+
+fn lookup(value: Value, segment: &str) -> CrushResult<Option<Value>> {
+    Ok(match value {
+        Value::Struct(s) => s.get(segment),
+        Value::List(l) => {
+            let idx = index(segment)?;
+            if idx < l.len() { Some(l.get(idx)?) } else { None }
+        }
+        Value::Table(t) => t
+            .rows()
+            .get(index(segment)?)
+            .map(|row| Value::Struct(row.clone().into_struct(t.types()))),
+        Value::Dict(d) => {
+            let key = match d.key_type() {
+                ValueType::Integer => Value::Integer(index(segment)? as i128),
+                _ => Value::string(segment),
+            };
+            d.get(&key)
+        }
+        v => return data_error(format!(
+            "Can't look up \"{}\" in a value of type {}",
+            segment,
+            v.value_type()
+        )),
+    })
+}
+
+fn path(context: CommandContext) -> CrushResult<()> {
+    let cfg: Path = Path::parse(context.arguments, &context.global_state.printer())?;
+    let mut value = match cfg.value {
+        Some(value) => value,
+        None => context.input.recv()?,
+    };
+    for segment in cfg.path.split('.').filter(|s| !s.is_empty()) {
+        value = match (lookup(value, segment)?, &cfg.default) {
+            (Some(v), _) => v,
+            (None, Some(default)) => return context.output.send(default.clone()),
+            (None, None) => return data_error(format!("Missing path segment \"{}\"", segment)),
+        };
+    }
+    context.output.send(value)
+}
+
 pub fn declare(root: &mut ScopeLoader) -> CrushResult<()> {
     root.create_namespace(
         "json",
@@ -191,6 +279,7 @@ pub fn declare(root: &mut ScopeLoader) -> CrushResult<()> {
         Box::new(move |env| {
             From::declare(env)?;
             To::declare(env)?;
+            Path::declare(env)?;
             Ok(())
         }),
     )?;
@@ -12,12 +12,16 @@ use std::convert::From;
 #[signature(
     from,
     can_block = true,
-    short = "Read specified files (or input) as a table with one line of text per row"
+    short = "Read specified files (or input) as a table with one line of text per row",
+    long = "Both \\n and \\r\\n line endings are supported. The input is read lazily, so large",
+    long = "files are never loaded into memory in their entirety.",
+    example = "lines:from some_file.txt",
+    example = "val \"a\\nb\" | lines:from",
 )]
 
 struct FromSignature {
     #[unnamed()]
-    #[description("the files to read from (read from input if no file is specified).")]
+    #[description("the files to read from (read from input if no file is specified, which must be a string, binary or binary_stream).")]
     files: Files,
 }
 
@@ -43,7 +47,7 @@ pub fn from(context: CommandContext) -> CrushResult<()> {
             s = &s[1..];
         }
         while s.ends_with('\r') {
-            s = &s[0..line.len() - 1];
+            s = &s[0..s.len() - 1];
         }
         output.send(Row::new(vec![Value::string(s)]))?;
         line.clear();
@@ -1,5 +1,6 @@
 use crate::lang::command::OutputType::Known;
-use crate::lang::errors::{argument_error_legacy, data_error, mandate, CrushResult, to_crush_error};
+use crate::lang::errors::{argument_error_legacy, data_error, io_error, mandate, CrushResult, to_crush_error};
+use crate::lang::files::Files;
 use crate::lang::data::list::List;
 use crate::lang::pretty::PrettyPrinter;
 use crate::lang::data::scope::Scope;
@@ -10,15 +11,19 @@ use rustyline::Editor;
 use std::path::PathBuf;
 use crate::lang::interactive::config_dir;
 
+mod base64;
 mod bin;
 mod csv;
+mod grep;
 mod http;
 mod json;
 mod lines;
 mod pup;
 mod split;
 mod toml;
+mod wc;
 mod words;
+mod write;
 mod yaml;
 
 #[signature(val,
@@ -59,6 +64,44 @@ pub fn dir(context: CommandContext) -> CrushResult<()> {
     )))
 }
 
+#[signature(cat,
+can_block = true,
+short = "Return the contents of one or more files",
+output = Known(ValueType::String),
+example = "cat ./README.md",
+example = "cat ./header.bin ./body.bin --binary",
+long = "If more than one file is given, their contents are concatenated in order.")]
+struct Cat {
+    #[unnamed()]
+    #[description("the files to read.")]
+    files: Files,
+    #[description("return the contents as binary data instead of a string.")]
+    #[default(false)]
+    binary: bool,
+}
+
+fn cat(context: CommandContext) -> CrushResult<()> {
+    let cfg: Cat = Cat::parse(context.arguments, &context.global_state.printer())?;
+    if !cfg.files.had_entries() {
+        return argument_error_legacy("Expected at least one file to read");
+    }
+    let mut data = Vec::new();
+    for path in Vec::<PathBuf>::from(cfg.files) {
+        match std::fs::read(&path) {
+            Ok(mut content) => data.append(&mut content),
+            Err(e) => return io_error(format!("Could not read file {}: {}", path.display(), e)),
+        }
+    }
+    if cfg.binary {
+        context.output.send(Value::Binary(data))
+    } else {
+        match String::from_utf8(data) {
+            Ok(s) => context.output.send(Value::String(s)),
+            Err(_) => data_error("File contents are not valid UTF-8, use --binary to read binary files"),
+        }
+    }
+}
+
 #[signature(echo, can_block = false, short = "Prints all arguments directly to the screen", output = Known(ValueType::Empty), example = "echo \"Hello, world!\"")]
 struct Echo {
     #[description("the values to print.")]
@@ -153,6 +196,7 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
         "io",
         "Data serialization I/O",
         Box::new(move |env| {
+            base64::declare(env)?;
             bin::declare(env)?;
             csv::declare(env)?;
             pup::declare(env)?;
@@ -164,6 +208,10 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
             yaml::declare(env)?;
 
             http::Http::declare(env)?;
+            grep::Grep::declare(env)?;
+            wc::Wc::declare(env)?;
+            Cat::declare(env)?;
+            write::WriteFile::declare(env)?;
             Echo::declare(env)?;
             Member::declare(env)?;
             Val::declare(env)?;
@@ -1,79 +1,42 @@
 use crate::lang::execution_context::CommandContext;
-use crate::{
-    lang::errors::CrushError,
-    lang::{data::table::Row, value::Value, value::ValueType},
-};
 use std::io::{BufReader, Read, Write};
 
 use crate::lang::command::OutputType::Unknown;
-use crate::lang::errors::{error, mandate, to_crush_error, CrushResult};
+use crate::lang::errors::{data_error, to_crush_error, CrushResult};
 use crate::lang::files::Files;
 use crate::lang::data::scope::ScopeLoader;
-use crate::lang::data::table::ColumnType;
-use crate::lang::{data::list::List, data::r#struct::Struct, data::table::Table};
 use signature::signature;
-use std::collections::HashSet;
-use std::convert::TryFrom;
+use super::json::{from_json, to_json};
 
-fn from_toml(toml_value: &toml::Value) -> CrushResult<Value> {
+/*
+Toml documents are converted to and from crush values by way of json values, so that toml,
+json and yaml all map to crush values in the same way.
+*/
+
+fn toml_to_json(toml_value: &toml::Value) -> serde_json::Value {
     match toml_value {
-        toml::Value::Boolean(b) => Ok(Value::Bool(*b)),
-        toml::Value::Float(f) => Ok(Value::Float(*f)),
-        toml::Value::Integer(f) => Ok(Value::Integer(*f as i128)),
-        toml::Value::String(s) => Ok(Value::string(s.as_str())),
-        toml::Value::Array(arr) => {
-            let mut lst = arr
-                .iter()
-                .map(|v| from_toml(v))
-                .collect::<CrushResult<Vec<Value>>>()?;
-            let types: HashSet<ValueType> = lst.iter().map(|v| v.value_type()).collect();
-            let struct_types: HashSet<Vec<ColumnType>> = lst
-                .iter()
-                .flat_map(|v| match v {
-                    Value::Struct(r) => vec![r.local_signature()],
-                    _ => vec![],
-                })
-                .collect();
+        toml::Value::Boolean(b) => serde_json::Value::from(*b),
+        toml::Value::Float(f) => serde_json::Value::from(*f),
+        toml::Value::Integer(i) => serde_json::Value::from(*i),
+        toml::Value::String(s) => serde_json::Value::from(s.as_str()),
+        toml::Value::Datetime(d) => serde_json::Value::from(d.to_string()),
+        toml::Value::Array(arr) => serde_json::Value::Array(arr.iter().map(toml_to_json).collect()),
+        toml::Value::Table(t) => serde_json::Value::Object(
+            t.iter().map(|(k, v)| (k.to_string(), toml_to_json(v))).collect()),
+    }
+}
 
-            match types.len() {
-                0 => Ok(Value::Empty()),
-                1 => {
-                    let list_type = types.iter().next().unwrap();
-                    match (list_type, struct_types.len()) {
-                        (ValueType::Struct, 1) => {
-                            let row_list = lst
-                                .drain(..)
-                                .map(|v| match v {
-                                    Value::Struct(r) => Ok(r.to_row()),
-                                    _ => error("Impossible!"),
-                                })
-                                .collect::<CrushResult<Vec<Row>>>()?;
-                            Ok(Value::Table(Table::new(
-                                struct_types.iter().next().unwrap().clone(),
-                                row_list,
-                            )))
-                        }
-                        _ => Ok(Value::List(List::new(list_type.clone(), lst))),
-                    }
-                }
-                _ => Ok(Value::List(List::new(ValueType::Any, lst))),
-            }
-        }
-        toml::Value::Table(t) => Ok(Value::Struct(Struct::new(
-            t.iter()
-                .map(|(k, v)| (k, from_toml(v)))
-                .map(|(k, v)| match v {
-                    Ok(vv) => Ok((k.to_string(), vv)),
-                    Err(e) => Err(e),
-                })
-                .collect::<Result<Vec<(String, Value)>, CrushError>>()?,
-            None,
-        ))),
-        toml::Value::Datetime(_) => {
-            unimplemented!(
-                "Dates in the TOML library are currently opaque blobs, dates are unsupported"
-            );
-        }
+/// Toml has no null value, so fields holding the empty value are left out.
+fn remove_nulls(json_value: serde_json::Value) -> serde_json::Value {
+    match json_value {
+        serde_json::Value::Array(arr) => serde_json::Value::Array(
+            arr.into_iter().map(remove_nulls).collect()),
+        serde_json::Value::Object(o) => serde_json::Value::Object(
+            o.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, remove_nulls(v)))
+                .collect()),
+        v => v,
     }
 }
 
@@ -82,7 +45,8 @@ from,
 can_block = true,
 output = Unknown,
 short = "Parse toml format",
-long = "Input can either be a binary stream or a file. All Toml types except\n    datetime are supported. Datetime is not supported because the rust toml\n    currently doesn't support accessing the internal state of a datetime.",
+long = "Input can either be a binary stream or a file. Tables are converted into structs, arrays",
+long = "into lists and datetimes into strings.",
 example = "toml:from Cargo.toml")]
 struct From {
     #[unnamed()]
@@ -96,62 +60,11 @@ fn from(context: CommandContext) -> CrushResult<()> {
 
     to_crush_error(reader.read_to_end(&mut v))?;
 
-    let v = to_crush_error(toml::from_slice(&v))?;
-    let crush_value = from_toml(&v)?;
-    context.output.send(crush_value)?;
-    Ok(())
-}
-
-fn to_toml(value: Value) -> CrushResult<toml::Value> {
-    match value.materialize()? {
-        Value::File(s) => Ok(toml::Value::from(mandate(s.to_str(), "Invalid filename")?)),
-
-        Value::String(s) => Ok(toml::Value::from(s.as_ref())),
-
-        Value::Integer(i) => Ok(toml::Value::from(to_crush_error(i64::try_from(i))?)),
-
-        Value::List(l) => Ok(toml::Value::Array(
-            l.dump()
-                .drain(..)
-                .map(to_toml)
-                .collect::<CrushResult<Vec<_>>>()?,
-        )),
-
-        Value::Table(t) => {
-            let types = t.types().to_vec();
-            let structs = t
-                .rows()
-                .iter()
-                .map(|r| r.clone().into_struct(&types))
-                .map(|s| to_toml(Value::Struct(s)))
-                .collect::<CrushResult<Vec<_>>>()?;
-            Ok(toml::Value::Array(structs))
-        }
-
-        Value::Bool(b) => Ok(toml::Value::from(b)),
-
-        Value::Float(f) => Ok(toml::Value::from(f)),
-
-        Value::Struct(s) => {
-            let mut map = toml::map::Map::new();
-            for (k, v) in s.local_elements() {
-                map.insert(k.to_string(), to_toml(v)?);
-            }
-            Ok(toml::Value::Table(map))
-        }
-
-        Value::Duration(d) => Ok(toml::Value::from(d.num_seconds())),
-
-        Value::Time(t) => Ok(toml::Value::from(t.to_rfc3339())),
-
-        Value::Binary(b) => Ok(toml::Value::from(b)),
-
-        Value::BinaryInputStream(_) => panic!("Impossible"),
-
-        Value::TableInputStream(_) => panic!("Impossible"),
-
-        v => error(&format!("Unsupported data type {}", v.value_type())),
-    }
+    let toml_value: toml::Value = match toml::from_slice(&v) {
+        Ok(v) => v,
+        Err(e) => return data_error(format!("Invalid toml: {}", e)),
+    };
+    context.output.send(from_json(&toml_to_json(&toml_value), false)?)
 }
 
 #[signature(
@@ -159,8 +72,10 @@ to,
 can_block = true,
 output = Unknown,
 short = "Serialize to toml format",
-long = "If no file is specified, output is returned as a BinaryStream.\n    The following Crush types are supported: File, string, integer, float, bool, list, table,\n    table_input_stream, struct, time, duration, binary and binary_stream.",
-example = "ls | toml:to")]
+long = "If no file is specified, output is returned as a BinaryStream. The value must be a struct.",
+long = "Toml has no null value, so fields holding the empty value are left out, and will be",
+long = "missing when the document is parsed again.",
+example = "data name=\"crush\" version=\"0.1.0\" | toml:to")]
 struct To {
     #[unnamed()]
     file: Files,
@@ -169,8 +84,11 @@ struct To {
 fn to(context: CommandContext) -> CrushResult<()> {
     let cfg: To = To::parse(context.arguments, &context.global_state.printer())?;
     let mut writer = cfg.file.writer(context.output)?;
-    let serde_value = context.input.recv()?;
-    let toml_value = to_toml(serde_value)?;
+    let json_value = remove_nulls(to_json(context.input.recv()?)?);
+    let toml_value = match toml::Value::try_from(json_value) {
+        Ok(v) => v,
+        Err(e) => return data_error(format!("Can't serialize to toml: {}", e)),
+    };
     to_crush_error(writer.write(toml_value.to_string().as_bytes()))?;
     Ok(())
 }
@@ -0,0 +1,114 @@
+use crate::lang::command::OutputType::Known;
+use crate::lang::data::r#struct::Struct;
+use crate::lang::errors::{argument_error_legacy, data_error, to_crush_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::files::Files;
+use crate::lang::value::{Value, ValueType};
+use signature::signature;
+use std::io::{BufRead, BufReader};
+
+#[signature(
+    wc,
+    can_block = true,
+    output = Known(ValueType::Struct),
+    short = "Count the lines, words and characters of the specified files (or input)",
+    long = "The input can be a stream with a single column of strings, e.g. the output of lines:from, in",
+    long = "which case every row counts as one line, or text, e.g. the output of cat. Words are separated",
+    long = "by unicode whitespace, and characters are counted in unicode scalar values, including newlines.",
+    long = "The result is a struct with the fields lines, words and chars. If any of the lines, words",
+    long = "or chars flags are given, only those fields are included.",
+    example = "lines:from some_file.txt | wc",
+    example = "wc some_file.txt --words",
+)]
+struct Wc {
+    #[unnamed()]
+    #[description("the files to count (read from input if no file is specified).")]
+    files: Files,
+    #[description("include the number of lines.")]
+    #[default(false)]
+    lines: bool,
+    #[description("include the number of words.")]
+    #[default(false)]
+    words: bool,
+    #[description("include the number of characters.")]
+    #[default(false)]
+    chars: bool,
+}
+
+#[derive(Default)]
+struct Counts {
+    lines: i128,
+    words: i128,
+    chars: i128,
+}
+
+impl Counts {
+    /// Count a single line, with the line ending already removed.
+    fn line(&mut self, line: &str) {
+        self.lines += 1;
+        self.words += line.split_whitespace().count() as i128;
+        self.chars += line.chars().count() as i128 + 1;
+    }
+
+    fn text(&mut self, text: &str) {
+        self.lines += text.matches('\n').count() as i128;
+        self.words += text.split_whitespace().count() as i128;
+        self.chars += text.chars().count() as i128;
+    }
+}
+
+pub fn wc(context: CommandContext) -> CrushResult<()> {
+    let cfg: Wc = Wc::parse(context.arguments, &context.global_state.printer())?;
+    let mut counts = Counts::default();
+
+    if cfg.files.had_entries() {
+        let mut reader = BufReader::new(cfg.files.reader(context.input)?);
+        let mut line = String::new();
+        while to_crush_error(reader.read_line(&mut line))? != 0 {
+            counts.text(&line);
+            line.clear();
+        }
+    } else {
+        match context.input.recv()? {
+            Value::String(s) => counts.text(&s),
+            Value::Binary(b) => counts.text(&to_crush_error(String::from_utf8(b))?),
+            Value::BinaryInputStream(b) => {
+                let mut reader = BufReader::new(b);
+                let mut line = String::new();
+                while to_crush_error(reader.read_line(&mut line))? != 0 {
+                    counts.text(&line);
+                    line.clear();
+                }
+            }
+            v => match v.stream() {
+                Some(mut input) => {
+                    if input.types().len() != 1 || input.types()[0].cell_type != ValueType::String {
+                        return data_error(
+                            "Expected an input stream containing a single column of type string");
+                    }
+                    while let Ok(row) = input.read() {
+                        match &row.cells()[0] {
+                            Value::String(s) => counts.line(s),
+                            _ => return data_error("Expected a string"),
+                        }
+                    }
+                }
+                None => return argument_error_legacy(
+                    "Expected either a file to read, text or a stream of lines"),
+            },
+        }
+    }
+
+    let all = !cfg.lines && !cfg.words && !cfg.chars;
+    let mut fields = Vec::new();
+    if all || cfg.lines {
+        fields.push(("lines", Value::Integer(counts.lines)));
+    }
+    if all || cfg.words {
+        fields.push(("words", Value::Integer(counts.words)));
+    }
+    if all || cfg.chars {
+        fields.push(("chars", Value::Integer(counts.chars)));
+    }
+    context.output.send(Value::Struct(Struct::new(fields, None)))
+}
@@ -0,0 +1,132 @@
+use crate::lang::errors::{argument_error_legacy, io_error, to_crush_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::files::Files;
+use crate::lang::value::{Value, ValueType};
+use crate::lang::command::OutputType::Known;
+use signature::signature;
+use std::convert::TryFrom;
+use std::fs::{create_dir_all, OpenOptions};
+use std::io::{copy, Write};
+use std::path::PathBuf;
+
+#[signature(
+write,
+can_block = true,
+output = Known(ValueType::Integer),
+short = "Write the input to a file and return the number of bytes written",
+long = "Binary data is written as is, table streams are written as one tab separated line per row",
+long = "and other values are written using their string representation. Use the format argument",
+long = "to write table streams as csv or any value as json instead.",
+example = "ls | write ./files.txt",
+example = "ps | write ./processes.json format=json",
+example = "\"hello\" | write ./logs/greeting.txt --append --parents")]
+pub struct WriteFile {
+    #[unnamed()]
+    #[description("the file to write to.")]
+    file: Files,
+    #[description("the output format.")]
+    #[values("text", "csv", "json")]
+    #[default("text")]
+    format: String,
+    #[description("append to the file instead of truncating it.")]
+    #[default(false)]
+    append: bool,
+    #[description("create any missing parent directories.")]
+    #[default(false)]
+    parents: bool,
+}
+
+/// A writer that keeps track of how many bytes have been written through it.
+struct CountingWriter<W: Write> {
+    inner: W,
+    count: usize,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn write_text(value: Value, out: &mut dyn Write) -> CrushResult<()> {
+    match value {
+        Value::Binary(b) => to_crush_error(out.write_all(&b)),
+        Value::BinaryInputStream(mut b) => {
+            to_crush_error(copy(b.as_mut(), out))?;
+            Ok(())
+        }
+        Value::String(s) => to_crush_error(out.write_all(s.as_bytes())),
+        value => match value.stream() {
+            Some(mut stream) => {
+                while let Ok(row) = stream.read() {
+                    let line = Vec::from(row)
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\t");
+                    to_crush_error(out.write_all(line.as_bytes()))?;
+                    to_crush_error(out.write_all(b"\n"))?;
+                }
+                Ok(())
+            }
+            None => to_crush_error(out.write_all(value.to_string().as_bytes())),
+        },
+    }
+}
+
+fn write_csv(value: Value, out: &mut dyn Write) -> CrushResult<()> {
+    match value.stream() {
+        Some(mut stream) => {
+            super::csv::write_record(out, stream.types().iter().map(|t| t.name.clone()), ',')?;
+            while let Ok(row) = stream.read() {
+                super::csv::write_record(out, Vec::from(row).iter().map(|v| v.to_string()), ',')?;
+            }
+            Ok(())
+        }
+        None => argument_error_legacy("The csv format requires a table stream as input"),
+    }
+}
+
+fn write_json(value: Value, out: &mut dyn Write) -> CrushResult<()> {
+    let json_value = super::json::to_json(value)?;
+    to_crush_error(out.write_all(to_crush_error(serde_json::to_string_pretty(&json_value))?.as_bytes()))
+}
+
+fn write(context: CommandContext) -> CrushResult<()> {
+    let cfg: WriteFile = WriteFile::parse(context.arguments, &context.global_state.printer())?;
+    let path = PathBuf::try_from(cfg.file)?;
+    let value = context.input.recv()?;
+
+    if cfg.parents {
+        if let Some(parent) = path.parent() {
+            if let Err(e) = create_dir_all(parent) {
+                return io_error(format!("Could not create directory {}: {}", parent.display(), e));
+            }
+        }
+    }
+
+    let file = match OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(cfg.append)
+        .truncate(!cfg.append)
+        .open(&path) {
+        Ok(f) => f,
+        Err(e) => return io_error(format!("Could not open file {}: {}", path.display(), e)),
+    };
+    let mut out = CountingWriter { inner: file, count: 0 };
+
+    match cfg.format.as_str() {
+        "csv" => write_csv(value, &mut out)?,
+        "json" => write_json(value, &mut out)?,
+        _ => write_text(value, &mut out)?,
+    }
+    to_crush_error(out.flush())?;
+    context.output.send(Value::Integer(out.count as i128))
+}
@@ -1,5 +1,5 @@
-use crate::lang::command::OutputType::Known;
-use crate::lang::errors::argument_error_legacy;
+use crate::lang::command::OutputType::{Known, Unknown};
+use crate::lang::errors::{argument_error_legacy, mandate, CrushError};
 use crate::lang::errors::CrushResult;
 use crate::lang::execution_context::ArgumentVector;
 use crate::lang::execution_context::CommandContext;
@@ -45,6 +45,58 @@ macro_rules! math_fun2 {
     };
 }
 
+/// Combine two numbers, using the integer operation if both are integers and the float
+/// operation otherwise.
+fn arithmetic(
+    a: Value,
+    b: Value,
+    int_op: impl Fn(i128, i128) -> CrushResult<i128>,
+    float_op: impl Fn(f64, f64) -> f64,
+) -> CrushResult<Value> {
+    match (a, b) {
+        (Value::Integer(x), Value::Integer(y)) => Ok(Value::Integer(int_op(x, y)?)),
+        (Value::Integer(x), Value::Float(y)) => Ok(Value::Float(float_op(x as f64, y))),
+        (Value::Float(x), Value::Integer(y)) => Ok(Value::Float(float_op(x, y as f64))),
+        (Value::Float(x), Value::Float(y)) => Ok(Value::Float(float_op(x, y))),
+        (a, b) => argument_error_legacy(&format!(
+            "Expected numbers, got a {} and a {}",
+            a.value_type(),
+            b.value_type()
+        )),
+    }
+}
+
+macro_rules! math_fold {
+    ($name:ident, $int_op:expr, $float_op:expr) => {
+        fn $name(mut context: CommandContext) -> CrushResult<()> {
+            context.arguments.check_len_min(2)?;
+            let mut res = context.arguments.value(0)?;
+            for idx in 1..context.arguments.len() {
+                res = arithmetic(res, context.arguments.value(idx)?, $int_op, $float_op)?;
+            }
+            context.output.send(res)
+        }
+    };
+}
+
+fn overflow() -> CrushError {
+    CrushError::from("Integer overflow")
+}
+
+math_fold!(add, |x: i128, y| x.checked_add(y).ok_or_else(overflow), |x: f64, y| x + y);
+math_fold!(sub, |x: i128, y| x.checked_sub(y).ok_or_else(overflow), |x: f64, y| x - y);
+math_fold!(mul, |x: i128, y| x.checked_mul(y).ok_or_else(overflow), |x: f64, y| x * y);
+math_fold!(
+    div,
+    |x: i128, y| mandate(x.checked_div(y), "Division by zero"),
+    |x: f64, y| x / y
+);
+math_fold!(
+    r#mod,
+    |x: i128, y| mandate(x.checked_rem_euclid(y), "Division by zero"),
+    |x: f64, y: f64| x.rem_euclid(y)
+);
+
 #[signature(
 sin,
 output = Known(ValueType::Float),
@@ -173,6 +225,58 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
                 Known(ValueType::Float),
                 vec![],
             )?;
+            env.declare_command(
+                "add",
+                add,
+                false,
+                "math:add term:(integer|float)...",
+                "The sum of all terms",
+                Some("    The result is an integer if all terms are integers, and a float otherwise."),
+                Unknown,
+                vec![],
+            )?;
+            env.declare_command(
+                "sub",
+                sub,
+                false,
+                "math:sub term:(integer|float)...",
+                "Subtract all remaining terms from the first one",
+                Some("    The result is an integer if all terms are integers, and a float otherwise."),
+                Unknown,
+                vec![],
+            )?;
+            env.declare_command(
+                "mul",
+                mul,
+                false,
+                "math:mul factor:(integer|float)...",
+                "The product of all factors",
+                Some("    The result is an integer if all factors are integers, and a float otherwise."),
+                Unknown,
+                vec![],
+            )?;
+            env.declare_command(
+                "div",
+                div,
+                false,
+                "math:div dividend:(integer|float) divisor:(integer|float)...",
+                "Divide the dividend by every divisor in turn",
+                Some(r#"    If all arguments are integers, integer division is used and dividing by zero
+    is an error. Otherwise, floating point division is used and dividing by zero
+    gives an infinite result."#),
+                Unknown,
+                vec![],
+            )?;
+            env.declare_command(
+                "mod",
+                r#mod,
+                false,
+                "math:mod number:(integer|float) divisor:(integer|float)...",
+                "The least non-negative residue after division",
+                None,
+                Unknown,
+                vec![],
+            )?;
             env.declare("pi", Value::Float(std::f64::consts::PI))?;
             env.declare("tau", Value::Float(std::f64::consts::PI * 2.0))?;
             env.declare("e", Value::Float(std::f64::consts::E))?;
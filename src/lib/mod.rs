@@ -9,6 +9,7 @@ use crate::lang::global_state::GlobalState;
 #[macro_use]
 pub mod binary_op;
 
+mod assert;
 mod comp;
 mod cond;
 mod constants;
@@ -18,6 +19,7 @@ mod crush;
 mod dbus;
 mod fd;
 mod fs;
+mod hash;
 mod host;
 mod io;
 mod math;
@@ -103,6 +105,7 @@ pub fn declare(
     global_state: &GlobalState,
     output: &ValueSender,
 ) -> CrushResult<()> {
+    assert::declare(root)?;
     comp::declare(root)?;
     cond::declare(root)?;
     constants::declare(root)?;
@@ -112,6 +115,7 @@ pub fn declare(
         dbus::declare(root)?;
     fd::declare(root)?;
     fs::declare(root)?;
+    hash::declare(root)?;
     host::declare(root)?;
     io::declare(root)?;
     math::declare(root)?;
@@ -10,7 +10,7 @@ use lazy_static::lazy_static;
 use nix::sys::signal;
 use nix::unistd::{Pid, Uid};
 use psutil::process::os::unix::ProcessExt;
-use psutil::process::{Process, ProcessResult, Status};
+use psutil::process::{Process, ProcessError, ProcessResult, Status};
 use signature::signature;
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -63,7 +63,12 @@ fn ps(context: CommandContext) -> CrushResult<()> {
     match psutil::process::processes() {
         Ok(procs) => {
             for proc in procs {
-                output.send(to_crush_error(ps_internal(proc, &users))?)?;
+                match ps_internal(proc, &users) {
+                    Ok(row) => output.send(row)?,
+                    // The process exited after it was listed
+                    Err(ProcessError::NoSuchProcess { .. }) => {}
+                    Err(e) => return to_crush_error(Err(e)),
+                }
             }
         }
         Err(_) => return error("Failed to list processes"),
@@ -0,0 +1,66 @@
+use crate::lang::errors::{argument_error_legacy, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::ColumnType;
+use crate::lang::pipe::Stream;
+use crate::lang::value::{Value, ValueType};
+use signature::signature;
+
+#[signature(
+chain,
+can_block = true,
+short = "Output all rows of the specified streams, one stream after another",
+long = "If the input is a stream, it is used as the first stream. All streams must have the",
+long = "same number of columns, and the columns must be of the same types as those of the",
+long = "first stream. The column names of the first stream are used for the output.",
+example = "chain (seq 3) (seq 5)",
+example = "seq 3 | chain (seq 5)")]
+pub struct Chain {
+    #[unnamed()]
+    #[description("the streams to output.")]
+    streams: Vec<Value>,
+}
+
+fn is_compatible(first: &[ColumnType], other: &[ColumnType]) -> bool {
+    first.len() == other.len()
+        && first
+        .iter()
+        .zip(other.iter())
+        .all(|(a, b)| a.cell_type == ValueType::Any || a.cell_type == b.cell_type)
+}
+
+pub fn chain(context: CommandContext) -> CrushResult<()> {
+    let cfg: Chain = Chain::parse(context.arguments, &context.global_state.printer())?;
+    let mut streams: Vec<Stream> = Vec::new();
+    if let Some(input) = context.input.recv()?.stream() {
+        streams.push(input);
+    }
+    for value in cfg.streams {
+        match value.stream() {
+            Some(stream) => streams.push(stream),
+            None => {
+                return argument_error_legacy(format!(
+                    "Expected a stream, got a {}",
+                    value.value_type().to_string()
+                ))
+            }
+        }
+    }
+    if streams.is_empty() {
+        return argument_error_legacy("Expected at least one stream");
+    }
+
+    let types = streams[0].types().to_vec();
+    for stream in &streams[1..] {
+        if !is_compatible(&types, stream.types()) {
+            return argument_error_legacy("All streams must have compatible column types");
+        }
+    }
+
+    let output = context.output.initialize(types)?;
+    for mut stream in streams {
+        while let Ok(row) = stream.read() {
+            output.send(row)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::lang::command::OutputType::Passthrough;
+use crate::lang::errors::{argument_error_legacy, error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::{ColumnVec, Row};
+use crate::lang::value::{Field, Value, ValueType};
+use signature::signature;
+
+#[signature(
+fill,
+short = "Replace empty values in a column with a default value",
+long = "Only the specified column is changed. The default value must be of the same type as the column.",
+example = "join id=a id=b --left | fill ^count 0",
+output = Passthrough)]
+pub struct Fill {
+    #[description("the column to fill.")]
+    field: Field,
+    #[description("the value to replace empty cells with.")]
+    value: Value,
+}
+
+fn fill(context: CommandContext) -> CrushResult<()> {
+    let cfg: Fill = Fill::parse(context.arguments, &context.global_state.printer())?;
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let idx = input.types().find(&cfg.field)?;
+            let column_type = &input.types()[idx].cell_type;
+            if *column_type != ValueType::Any && *column_type != cfg.value.value_type() {
+                return argument_error_legacy(format!(
+                    "Can't fill column of type {} with a value of type {}",
+                    column_type, cfg.value.value_type()));
+            }
+            let output = context.output.initialize(input.types().to_vec())?;
+            while let Ok(row) = input.read() {
+                let mut cells = Vec::from(row);
+                if let Value::Empty() = cells[idx] {
+                    cells[idx] = cfg.value.clone();
+                }
+                output.send(Row::new(cells))?;
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
@@ -0,0 +1,61 @@
+use crate::lang::errors::{argument_error_legacy, error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::ColumnVec;
+use crate::lang::data::table::Row;
+use crate::lang::value::{Field, Value, ValueType};
+use signature::signature;
+use crate::lang::command::OutputType::Unknown;
+
+#[signature(
+flatten,
+output = Unknown,
+short = "Output one row for every element of a list column",
+long = "All other columns are copied as is. Rows where the list is empty are dropped.",
+example = "articles | flatten ^tags")]
+pub struct Flatten {
+    #[description("the list column to flatten.")]
+    column: Field,
+}
+
+pub fn flatten(context: CommandContext) -> CrushResult<()> {
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let cfg: Flatten = Flatten::parse(context.arguments, &context.global_state.printer())?;
+            let idx = input.types().find(&cfg.column)?;
+            let mut types = input.types().to_vec();
+            let element_type = match &types[idx].cell_type {
+                ValueType::List(element_type) => *element_type.clone(),
+                ValueType::Any => ValueType::Any,
+                t => {
+                    return argument_error_legacy(format!(
+                        "Expected column {} to be a list, was {}",
+                        types[idx].name,
+                        t.to_string()
+                    ))
+                }
+            };
+            types[idx].cell_type = element_type;
+            let output = context.output.initialize(types)?;
+            while let Ok(row) = input.read() {
+                let cells = Vec::from(row);
+                match &cells[idx] {
+                    Value::List(list) => {
+                        for element in list.dump() {
+                            let mut new_cells = cells.clone();
+                            new_cells[idx] = element;
+                            output.send(Row::new(new_cells))?;
+                        }
+                    }
+                    v => {
+                        return argument_error_legacy(format!(
+                            "Expected a list, got a {}",
+                            v.value_type().to_string()
+                        ))
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ => error("Expected io to be a stream"),
+    }
+}
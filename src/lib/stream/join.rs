@@ -8,7 +8,7 @@ use crate::lang::pipe::OutputStream;
 use crate::lang::data::table::ColumnType;
 use crate::lang::data::table::ColumnVec;
 use crate::lang::data::table::Row;
-use crate::lang::value::Value;
+use crate::lang::value::{Value, ValueType};
 use std::collections::HashMap;
 use crate::lang::ordered_string_map::OrderedStringMap;
 use signature::signature;
@@ -28,30 +28,48 @@ fn do_join(
     left_idx: usize,
     mut r: Stream,
     right_idx: usize,
+    left_outer: bool,
     output: &OutputStream,
     printer: &Printer,
 ) -> CrushResult<()> {
-    let mut l_data: HashMap<Value, Row> = HashMap::new();
+    let mut r_data: HashMap<Value, Vec<Row>> = HashMap::new();
 
-    while let Ok(row) = l.read() {
-        l_data.insert(row.cells()[left_idx].clone(), row);
+    while let Ok(row) = r.read() {
+        r_data
+            .entry(row.cells()[right_idx].clone())
+            .or_insert_with(Vec::new)
+            .push(row);
     }
 
-    while let Ok(r_row) = r.read() {
-        l_data
-            .remove(&r_row.cells()[right_idx])
-            .map(|l_row| {
-                printer.handle_error(output.send(combine(l_row, r_row, right_idx)));
-            });
+    let empty = Row::new(r.types().iter().map(|_| Value::Empty()).collect());
+
+    while let Ok(l_row) = l.read() {
+        match r_data.get(&l_row.cells()[left_idx]) {
+            Some(r_rows) => {
+                for r_row in r_rows {
+                    printer.handle_error(output.send(combine(l_row.clone(), r_row.clone(), right_idx)));
+                }
+            }
+            None => {
+                if left_outer {
+                    printer.handle_error(output.send(combine(l_row, empty.clone(), right_idx)));
+                }
+            }
+        }
     }
     Ok(())
 }
 
-fn get_output_type(left_type: &[ColumnType], right_type: &[ColumnType], right_key_idx: usize) -> Result<Vec<ColumnType>, CrushError> {
+fn get_output_type(left_type: &[ColumnType], right_type: &[ColumnType], right_key_idx: usize, left_outer: bool) -> Result<Vec<ColumnType>, CrushError> {
     let mut res = left_type.to_vec();
     for (idx, c) in right_type.iter().enumerate() {
         if idx != right_key_idx {
-            res.push(c.clone());
+            if left_outer {
+                // Unmatched left rows have empty right columns
+                res.push(ColumnType::new(&c.name, ValueType::Any));
+            } else {
+                res.push(c.clone());
+            }
         }
     }
     Ok(res)
@@ -61,14 +79,26 @@ fn get_output_type(left_type: &[ColumnType], right_type: &[ColumnType], right_ke
 join,
 output = Unknown,
 short = "Join two streams together on the specified keys.",
-example = "join user=(ll) name=(user:list)")]
+long = "The right stream is read into memory, and the left stream is then read one row at a time.",
+long = "Every combination of left and right rows with equal keys is emitted. With the left flag,",
+long = "left rows without a matching right row are also emitted, with the right columns empty.",
+long = "In that case, all right columns are of type any.",
+example = "join user=(ll) name=(user:list)",
+example = "join user=(ll) name=(user:list) --left")]
 pub struct Join {
     #[named()]
     #[description("Field to join")]
     join: OrderedStringMap<Stream>,
+    #[description("also emit left rows without a match.")]
+    #[default(false)]
+    left: bool,
 }
 
 pub fn join(mut context: CommandContext) -> CrushResult<()> {
+    let cfg: Join = Join::parse(context.arguments.clone(), &context.global_state.printer())?;
+    // The streams are taken directly from the arguments rather than from the parsed map,
+    // since both sides are often joined on columns with the same name.
+    context.arguments.retain(|a| !matches!((a.argument_type.as_deref(), &a.value), (Some("left"), Value::Bool(_))));
     context.arguments.check_len(2)?;
     let l = context.arguments.remove(0);
     let r = context.arguments.remove(0);
@@ -77,11 +107,20 @@ pub fn join(mut context: CommandContext) -> CrushResult<()> {
         (Some(left_name), left_stream, Some(right_name), right_stream) => {
             let left_idx = left_stream.types().find_str(&left_name)?;
             let right_idx = right_stream.types().find_str(&right_name)?;
+            let left_key_type = &left_stream.types()[left_idx].cell_type;
+            let right_key_type = &right_stream.types()[right_idx].cell_type;
+            if left_key_type != right_key_type {
+                return argument_error_legacy(format!(
+                    "Can't join on keys of different types, {} and {}",
+                    left_key_type.to_string(),
+                    right_key_type.to_string()
+                ));
+            }
 
-            let output_type = get_output_type(left_stream.types(), right_stream.types(), right_idx)?;
+            let output_type = get_output_type(left_stream.types(), right_stream.types(), right_idx, cfg.left)?;
             let output = context.output.initialize(output_type)?;
 
-            do_join(left_stream, left_idx, right_stream, right_idx, &output, &context.global_state.printer())
+            do_join(left_stream, left_idx, right_stream, right_idx, cfg.left, &output, &context.global_state.printer())
         }
         (_, _, _, _) => argument_error_legacy("Invalid inputs for joins"),
     }
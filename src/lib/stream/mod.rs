@@ -2,21 +2,36 @@ use crate::lang::command::OutputType::Unknown;
 use crate::lang::errors::CrushResult;
 use crate::lang::data::scope::Scope;
 
+mod chain;
 mod count;
 mod drop;
 mod each;
 mod enumerate;
+mod fill;
+mod flatten;
 mod group;
 mod head;
 mod join;
+mod pivot;
+mod pmap;
+mod reduce;
+mod rename;
+mod repeat;
 mod reverse;
+mod sample;
 mod select;
 mod seq;
+mod shuffle;
 mod sort;
 mod sum_avg;
 mod tail;
+mod take_while;
+mod tee;
+mod transpose;
 mod uniq;
+mod window;
 mod r#where;
+mod with_timeout;
 mod zip;
 
 pub fn declare(root: &Scope) -> CrushResult<()> {
@@ -24,17 +39,31 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
         "stream",
         "Stream handling commands",
         Box::new(move |env| {
+            chain::Chain::declare(env)?;
             count::Count::declare(env)?;
             drop::Drop::declare(env)?;
             each::Each::declare(env)?;
+            pmap::Pmap::declare(env)?;
             enumerate::Enumerate::declare(env)?;
+            fill::Fill::declare(env)?;
+            flatten::Flatten::declare(env)?;
             head::Head::declare(env)?;
             tail::Tail::declare(env)?;
+            take_while::TakeWhile::declare(env)?;
+            take_while::DropWhile::declare(env)?;
+            tee::Tee::declare(env)?;
             r#where::Where::declare(env)?;
             sort::Sort::declare(env)?;
+            sort::SortBy::declare(env)?;
+            shuffle::Shuffle::declare(env)?;
             reverse::Reverse::declare(env)?;
+            transpose::Transpose::declare(env)?;
+            rename::Rename::declare(env)?;
+            rename::Cast::declare(env)?;
             group::Group::declare(env)?;
+            pivot::Pivot::declare(env)?;
             uniq::Uniq::declare(env)?;
+            uniq::DistinctBy::declare(env)?;
             env.declare_command(
                 "join", join::join, true,
                 "join left:field right:field", "Join two streams together on the specified keys", None,
@@ -53,7 +82,14 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
                 vec![],
             )?;
             seq::Seq::declare(env)?;
+            repeat::Repeat::declare(env)?;
+            reduce::Reduce::declare(env)?;
+            reduce::Scan::declare(env)?;
+            sample::Sample::declare(env)?;
             zip::Zip::declare(env)?;
+            with_timeout::WithTimeout::declare(env)?;
+            window::Window::declare(env)?;
+            window::Chunk::declare(env)?;
             Ok(())
         }))?;
     root.r#use(&e);
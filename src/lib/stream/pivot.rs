@@ -0,0 +1,117 @@
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::Unknown;
+use crate::lang::errors::{argument_error_legacy, error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::{ColumnType, ColumnVec, Row, Table};
+use crate::lang::pipe::pipe;
+use crate::lang::value::{Field, Value, ValueType};
+use signature::signature;
+use std::collections::HashMap;
+
+#[signature(
+pivot,
+can_block = true,
+output = Unknown,
+short = "Cross-tabulate the input",
+long = "The output has one row for each distinct value of the rows column, and one column for each",
+long = "distinct value of the cols column. Each cell holds the value of the values column for that",
+long = "combination, or empty if there is no such row in the input, so these columns are of type any.",
+long = "If the same combination occurs more than once, an aggregate command must be given. It receives",
+long = "all values for a combination as a single column stream, like the aggregation commands of group.",
+long = "Because the output columns depend on the data, the whole input is read into memory first.",
+example = "sales | pivot rows=^region cols=^year values=^amount aggregate={sum}")]
+pub struct Pivot {
+    #[description("the column whose distinct values become output rows.")]
+    rows: Field,
+    #[description("the column whose distinct values become output columns.")]
+    cols: Field,
+    #[description("the column holding the cell values.")]
+    values: Field,
+    #[description("the command used to combine multiple values for the same cell.")]
+    aggregate: Option<Command>,
+}
+
+/// Return the position of the value in the list of distinct values, adding it if it's new.
+fn position(value: &Value, distinct: &mut Vec<Value>, lookup: &mut HashMap<Value, usize>) -> usize {
+    match lookup.get(value) {
+        Some(idx) => *idx,
+        None => {
+            lookup.insert(value.clone(), distinct.len());
+            distinct.push(value.clone());
+            distinct.len() - 1
+        }
+    }
+}
+
+fn pivot(context: CommandContext) -> CrushResult<()> {
+    let cfg: Pivot = Pivot::parse(context.arguments.clone(), &context.global_state.printer())?;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let types = input.types().to_vec();
+            let row_idx = types.find(&cfg.rows)?;
+            let col_idx = types.find(&cfg.cols)?;
+            let value_idx = types.find(&cfg.values)?;
+
+            let mut row_values = Vec::new();
+            let mut row_lookup = HashMap::new();
+            let mut col_values = Vec::new();
+            let mut col_lookup = HashMap::new();
+            let mut cells: HashMap<(usize, usize), Vec<Value>> = HashMap::new();
+
+            while let Ok(row) = input.read() {
+                for idx in &[row_idx, col_idx] {
+                    if !row.cells()[*idx].value_type().is_hashable() {
+                        return argument_error_legacy(format!(
+                            "Column {} contains a value of type {}, which is not hashable",
+                            types[*idx].name, row.cells()[*idx].value_type()));
+                    }
+                }
+                let r = position(&row.cells()[row_idx], &mut row_values, &mut row_lookup);
+                let c = position(&row.cells()[col_idx], &mut col_values, &mut col_lookup);
+                let values = cells.entry((r, c)).or_insert_with(Vec::new);
+                if !values.is_empty() && cfg.aggregate.is_none() {
+                    return argument_error_legacy(format!(
+                        "Multiple values for {}={} and {}={}, use aggregate to combine them",
+                        types[row_idx].name, row_values[r], types[col_idx].name, col_values[c]));
+                }
+                values.push(row.cells()[value_idx].clone());
+            }
+
+            // Combinations missing from the input are empty, so the pivoted columns may hold any type
+            let mut output_types = vec![types[row_idx].clone()];
+            output_types.extend(col_values.iter().map(|v| ColumnType::new(&v.to_string(), ValueType::Any)));
+            let output = context.output.initialize(output_types)?;
+
+            for (r, row_value) in row_values.drain(..).enumerate() {
+                let mut result = vec![row_value];
+                for c in 0..col_values.len() {
+                    result.push(match (cells.remove(&(r, c)), &cfg.aggregate) {
+                        (None, _) => Value::Empty(),
+                        (Some(mut values), None) => values.remove(0),
+                        (Some(values), Some(aggregate)) => {
+                            let (input_sender, input_receiver) = pipe();
+                            let (output_sender, output_receiver) = pipe();
+                            input_sender.send(Value::Table(Table::new(
+                                vec![types[value_idx].clone()],
+                                values.into_iter().map(|v| Row::new(vec![v])).collect())))?;
+                            drop(input_sender);
+                            aggregate.invoke(CommandContext {
+                                input: input_receiver,
+                                output: output_sender,
+                                arguments: vec![],
+                                scope: context.scope.clone(),
+                                this: None,
+                                global_state: context.global_state.clone(),
+                            })?;
+                            output_receiver.recv()?
+                        }
+                    });
+                }
+                output.send(Row::new(result))?;
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
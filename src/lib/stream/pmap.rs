@@ -0,0 +1,160 @@
+use crate::lang::argument::Argument;
+use crate::lang::ast::Location;
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::Unknown;
+use crate::lang::errors::{argument_error_legacy, error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::global_state::GlobalState;
+use crate::lang::data::scope::Scope;
+use crate::lang::pipe::{empty_channel, pipe, OutputStream, DEFAULT_STREAM_CAPACITY};
+use crate::lang::data::table::{ColumnType, Row};
+use crate::lang::value::{Value, ValueType};
+use crossbeam::{bounded, Receiver, Sender};
+use signature::signature;
+use std::collections::HashMap;
+
+#[signature(
+pmap,
+can_block = true,
+output = Unknown,
+short = "Run a command once for each row of input, using multiple threads",
+long = "The columns of the row are passed to the command as named arguments. The output",
+long = "of every invocation becomes a row in the output stream. Because invocations run in",
+long = "parallel, rows are output in the order they are finished, unless the ordered flag",
+long = "is given. The buffer argument limits how many output rows are held before the",
+long = "workers block.",
+example = "find . | pmap workers=8 {|file| md5 file}")]
+pub struct Pmap {
+    #[description("the command to run.")]
+    body: Command,
+    #[description("the number of threads to run the command on.")]
+    #[default(4usize)]
+    workers: usize,
+    #[description("output rows in the same order as the corresponding input rows.")]
+    #[default(false)]
+    ordered: bool,
+    #[description("the number of output rows to buffer before the workers block.")]
+    buffer: Option<usize>,
+}
+
+fn work(
+    body: Command,
+    location: Location,
+    types: Vec<ColumnType>,
+    scope: Scope,
+    global_state: GlobalState,
+    tasks: Receiver<(usize, Row)>,
+    results: Sender<(usize, Option<Value>)>,
+) -> CrushResult<()> {
+    while let Ok((idx, row)) = tasks.recv() {
+        let arguments = Vec::from(row)
+            .drain(..)
+            .zip(types.iter())
+            .map(|(c, t)| Argument::named(t.name.as_ref(), c, location))
+            .collect();
+        let (sender, receiver) = pipe();
+        let res = body.invoke(CommandContext {
+            input: empty_channel(),
+            output: sender,
+            arguments,
+            scope: scope.clone(),
+            this: None,
+            global_state: global_state.clone(),
+        });
+        let value = match res {
+            Ok(()) => receiver.recv().ok(),
+            Err(e) => {
+                global_state.printer().crush_error(e);
+                None
+            }
+        };
+        if results.send((idx, value)).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn collect(
+    ordered: bool,
+    results: Receiver<(usize, Option<Value>)>,
+    output: OutputStream,
+) -> CrushResult<()> {
+    if ordered {
+        let mut pending = HashMap::new();
+        let mut next = 0usize;
+        while let Ok((idx, value)) = results.recv() {
+            pending.insert(idx, value);
+            while let Some(value) = pending.remove(&next) {
+                if let Some(value) = value {
+                    output.send(Row::new(vec![value]))?;
+                }
+                next += 1;
+            }
+        }
+    } else {
+        while let Ok((_, value)) = results.recv() {
+            if let Some(value) = value {
+                output.send(Row::new(vec![value]))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn pmap(context: CommandContext) -> CrushResult<()> {
+    let cfg: Pmap = Pmap::parse(context.arguments.clone(), &context.global_state.printer())?;
+    if cfg.workers == 0 {
+        return argument_error_legacy("pmap: The number of workers must be at least one");
+    }
+    let capacity = cfg.buffer.unwrap_or(DEFAULT_STREAM_CAPACITY);
+    if capacity == 0 {
+        return argument_error_legacy("pmap: The buffer size must be at least one");
+    }
+    let location = context
+        .arguments
+        .get(0)
+        .map(|a| a.location)
+        .unwrap_or(Location::new(0, 0));
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let output = context
+                .output
+                .initialize_bounded(vec![ColumnType::new("value", ValueType::Any)], capacity)?;
+            let (task_send, task_recv) = bounded(cfg.workers);
+            let (result_send, result_recv) = bounded(cfg.workers);
+
+            for _ in 0..cfg.workers {
+                let my_body = cfg.body.copy();
+                let my_types = input.types().to_vec();
+                let my_scope = context.scope.create_child(&context.scope, false);
+                let my_state = context.global_state.clone();
+                let my_tasks = task_recv.clone();
+                let my_results = result_send.clone();
+                context.global_state.threads().spawn(
+                    "pmap:worker",
+                    move || work(my_body, location, my_types, my_scope, my_state, my_tasks, my_results),
+                )?;
+            }
+            drop(task_recv);
+            drop(result_send);
+
+            let ordered = cfg.ordered;
+            context.global_state.threads().spawn(
+                "pmap:collect",
+                move || collect(ordered, result_recv, output),
+            )?;
+
+            let mut idx = 0usize;
+            while let Ok(row) = input.read() {
+                if task_send.send((idx, row)).is_err() {
+                    break;
+                }
+                idx += 1;
+            }
+            Ok(())
+        }
+        None => error("pmap: Expected a stream"),
+    }
+}
@@ -0,0 +1,122 @@
+use crate::lang::argument::Argument;
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::Unknown;
+use crate::lang::errors::{error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::{ColumnType, Row};
+use crate::lang::pipe::{black_hole, empty_channel};
+use crate::lang::value::Value;
+use signature::signature;
+use super::r#where::call_with;
+
+#[signature(
+reduce,
+can_block = true,
+output = Unknown,
+short = "Combine all rows of the input into a single value",
+long = "The closure is invoked once per row, with the current accumulator as the argument acc and",
+long = "the columns of the row as named arguments. Its output becomes the new accumulator.",
+long = "The output is a single row containing the final accumulator. If the input is empty, this is the seed.",
+example = "seq 10 | reduce 0 {|acc value| acc + value}")]
+pub struct Reduce {
+    #[description("the initial value of the accumulator.")]
+    seed: Value,
+    #[description("the closure used to update the accumulator.")]
+    body: Command,
+}
+
+pub fn reduce(context: CommandContext) -> CrushResult<()> {
+    let cfg: Reduce = Reduce::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let location = context.arguments[0].location;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let base_context = CommandContext {
+                input: empty_channel(),
+                output: black_hole(),
+                arguments: vec![],
+                scope: context.scope.clone(),
+                this: None,
+                global_state: context.global_state.clone(),
+            };
+
+            let mut acc = cfg.seed;
+            while let Ok(row) = input.read() {
+                acc = call_with(
+                    cfg.body.copy(),
+                    location,
+                    vec![Argument::named("acc", acc, location)],
+                    &row,
+                    input.types(),
+                    &base_context)?;
+            }
+
+            let output = context.output.initialize(vec![ColumnType::new("value", acc.value_type())])?;
+            output.send(Row::new(vec![acc]))
+        }
+        None => error("Expected a stream"),
+    }
+}
+
+#[signature(
+scan,
+can_block = true,
+output = Unknown,
+short = "Output a running accumulation over all rows of the input",
+long = "Like reduce, but outputs the accumulator after each row, so the output has as many rows as the input.",
+long = "The type of the output column is the type of the first accumulator produced. It is an error for",
+long = "the closure to later return a value of a different type.",
+example = "seq 10 | scan 0 {|acc value| acc + value}")]
+pub struct Scan {
+    #[description("the initial value of the accumulator.")]
+    seed: Value,
+    #[description("the closure used to update the accumulator.")]
+    body: Command,
+}
+
+pub fn scan(context: CommandContext) -> CrushResult<()> {
+    let cfg: Scan = Scan::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let location = context.arguments[0].location;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let base_context = CommandContext {
+                input: empty_channel(),
+                output: black_hole(),
+                arguments: vec![],
+                scope: context.scope.clone(),
+                this: None,
+                global_state: context.global_state.clone(),
+            };
+
+            let mut acc = cfg.seed;
+            let mut output = None;
+            while let Ok(row) = input.read() {
+                acc = call_with(
+                    cfg.body.copy(),
+                    location,
+                    vec![Argument::named("acc", acc, location)],
+                    &row,
+                    input.types(),
+                    &base_context)?;
+                if output.is_none() {
+                    output = Some((
+                        acc.value_type(),
+                        context.output.initialize(vec![ColumnType::new("value", acc.value_type())])?));
+                }
+                let (output_type, out) = output.as_ref().unwrap();
+                if *output_type != acc.value_type() {
+                    return error(format!(
+                        "Accumulator changed type from {} to {}", output_type, acc.value_type()));
+                }
+                out.send(Row::new(vec![acc.clone()]))?;
+            }
+
+            if output.is_none() {
+                context.output.initialize(vec![ColumnType::new("value", acc.value_type())])?;
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
@@ -0,0 +1,78 @@
+use crate::lang::command::OutputType::Unknown;
+use crate::lang::errors::{data_error, error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::{ColumnVec, Row};
+use crate::lang::ordered_string_map::OrderedStringMap;
+use crate::lang::value::{Field, ValueType};
+use signature::signature;
+
+#[signature(
+rename,
+short = "Rename columns of the input",
+long = "Only the column names are changed, the rows are passed on as is.",
+example = "ps | rename pid=\"id\" name=\"command\"",
+output = Unknown)]
+pub struct Rename {
+    #[named()]
+    #[description("the columns to rename, mapped to their new names.")]
+    columns: OrderedStringMap<String>,
+}
+
+fn rename(context: CommandContext) -> CrushResult<()> {
+    let cfg: Rename = Rename::parse(context.arguments, &context.global_state.printer())?;
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let mut types = input.types().to_vec();
+            for (old, new) in cfg.columns.iter() {
+                let idx = input.types().find_str(old)?;
+                types[idx].name = new.clone();
+            }
+            let output = context.output.initialize(types)?;
+            while let Ok(row) = input.read() {
+                output.send(row)?;
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
+
+#[signature(
+cast,
+can_block = true,
+short = "Convert the values of a column to the specified type",
+long = "The conversion is done in the same way as by the convert command.",
+example = "csv:from data.csv | cast ^size integer",
+output = Unknown)]
+pub struct Cast {
+    #[description("the column to convert.")]
+    field: Field,
+    #[description("the type to convert the column to.")]
+    target_type: ValueType,
+}
+
+fn cast(context: CommandContext) -> CrushResult<()> {
+    let cfg: Cast = Cast::parse(context.arguments, &context.global_state.printer())?;
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let idx = input.types().find(&cfg.field)?;
+            let mut types = input.types().to_vec();
+            types[idx].cell_type = cfg.target_type.clone();
+            let output = context.output.initialize(types)?;
+            let mut row_idx = 0;
+            while let Ok(row) = input.read() {
+                let mut cells = Vec::from(row);
+                let value = cells[idx].clone();
+                cells[idx] = match value.clone().convert(cfg.target_type.clone()) {
+                    Ok(v) => v,
+                    Err(_) => return data_error(format!(
+                        "Can't convert value {} of row {} to type {}", value, row_idx, cfg.target_type)),
+                };
+                output.send(Row::new(cells))?;
+                row_idx += 1;
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
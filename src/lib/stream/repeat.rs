@@ -0,0 +1,42 @@
+use crate::lang::errors::{argument_error_legacy, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::ColumnType;
+use crate::lang::{data::table::Row, value::Value};
+use signature::signature;
+
+#[signature(
+repeat,
+can_block = true,
+short = "Return a stream containing the same value repeatedly",
+example = "repeat \"hello\" 3",
+example = "repeat 1 --forever | head 5")]
+#[derive(Debug)]
+pub struct Repeat {
+    #[description("the value to repeat.")]
+    value: Value,
+    #[description("the number of times to repeat the value.")]
+    count: Option<usize>,
+    #[description("repeat the value until the output is closed.")]
+    #[default(false)]
+    forever: bool,
+}
+
+pub fn repeat(context: CommandContext) -> CrushResult<()> {
+    let cfg: Repeat = Repeat::parse(context.arguments, &context.global_state.printer())?;
+    let output = context
+        .output
+        .initialize(vec![ColumnType::new("value", cfg.value.value_type())])?;
+
+    match (cfg.count, cfg.forever) {
+        (Some(count), false) => {
+            for _ in 0..count {
+                output.send(Row::new(vec![cfg.value.clone()]))?;
+            }
+            Ok(())
+        }
+        (None, true) => loop {
+            output.send(Row::new(vec![cfg.value.clone()]))?;
+        },
+        _ => argument_error_legacy("Expected exactly one of a count or the forever flag"),
+    }
+}
@@ -0,0 +1,79 @@
+use crate::lang::errors::{argument_error_legacy, error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::command::OutputType::Passthrough;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use signature::signature;
+
+#[signature(
+sample,
+can_block = true,
+output = Passthrough,
+short = "Return a random sample of the rows of the input",
+long = "When a number of rows is given, reservoir sampling is used, so that only that many",
+long = "rows are kept in memory, and the sample is emitted once the input has been read. If",
+long = "the input has fewer rows than requested, all rows are returned.",
+long = "",
+long = "When a ratio is given, every row is kept with that probability, and rows are emitted",
+long = "as they are read.",
+example = "seq 1000 | sample 10",
+example = "seq 1000 | sample ratio=0.1")]
+pub struct Sample {
+    #[description("the number of rows to return.")]
+    rows: Option<usize>,
+    #[description("the probability of keeping each row.")]
+    ratio: Option<f64>,
+    #[description("seed the random number generator, for a reproducible sample.")]
+    seed: Option<i128>,
+}
+
+pub(super) fn rng(seed: Option<i128>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed as u64),
+        None => StdRng::from_entropy(),
+    }
+}
+
+pub fn sample(context: CommandContext) -> CrushResult<()> {
+    let cfg: Sample = Sample::parse(context.arguments, &context.global_state.printer())?;
+    let mut rng = rng(cfg.seed);
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let output = context.output.initialize(input.types().to_vec())?;
+            match (cfg.rows, cfg.ratio) {
+                (Some(rows), None) => {
+                    let mut reservoir = Vec::with_capacity(rows);
+                    let mut seen = 0usize;
+                    while let Ok(row) = input.read() {
+                        if reservoir.len() < rows {
+                            reservoir.push(row);
+                        } else {
+                            let idx = rng.gen_range(0, seen + 1);
+                            if idx < rows {
+                                reservoir[idx] = row;
+                            }
+                        }
+                        seen += 1;
+                    }
+                    for row in reservoir {
+                        output.send(row)?;
+                    }
+                    Ok(())
+                }
+                (None, Some(ratio)) => {
+                    if !(0.0..=1.0).contains(&ratio) {
+                        return argument_error_legacy("Ratio must be between 0 and 1");
+                    }
+                    while let Ok(row) = input.read() {
+                        if rng.gen::<f64>() < ratio {
+                            output.send(row)?;
+                        }
+                    }
+                    Ok(())
+                }
+                _ => argument_error_legacy("Expected exactly one of a number of rows or a ratio"),
+            }
+        }
+        None => error("Expected a stream"),
+    }
+}
@@ -0,0 +1,38 @@
+use crate::lang::command::OutputType::Passthrough;
+use crate::lang::errors::{error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use rand::seq::SliceRandom;
+use signature::signature;
+use super::sample::rng;
+
+#[signature(
+shuffle,
+short = "Return the rows of the input in random order",
+long = "All rows of the input are kept in memory until the input has been read, so shuffling",
+long = "a large stream is expensive. Combine with head to pick a few random rows of a small input.",
+example = "seq 10 | shuffle",
+example = "ls | shuffle seed=1 | head 3",
+output = Passthrough)]
+pub struct Shuffle {
+    #[description("seed the random number generator, for a reproducible order.")]
+    seed: Option<i128>,
+}
+
+fn shuffle(context: CommandContext) -> CrushResult<()> {
+    let cfg: Shuffle = Shuffle::parse(context.arguments, &context.global_state.printer())?;
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let output = context.output.initialize(input.types().to_vec())?;
+            let mut res = Vec::new();
+            while let Ok(row) = input.read() {
+                res.push(row);
+            }
+            res.shuffle(&mut rng(cfg.seed));
+            for row in res {
+                output.send(row)?;
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
@@ -1,12 +1,15 @@
+use crate::lang::command::Command;
 use crate::lang::command::OutputType::Passthrough;
-use crate::lang::errors::{error, CrushResult};
+use crate::lang::errors::{argument_error, error, CrushResult};
 use crate::lang::execution_context::CommandContext;
 use crate::lang::data::table::ColumnVec;
 use crate::lang::data::table::Row;
-use crate::lang::value::Field;
+use crate::lang::pipe::{black_hole, empty_channel};
+use crate::lang::value::{Field, Value};
 use crate::lang::errors::argument_error_legacy;
 use signature::signature;
 use std::cmp::Ordering;
+use super::r#where::call;
 
 #[signature(
 sort,
@@ -82,3 +85,69 @@ fn sort(context: CommandContext) -> CrushResult<()> {
         None => error("Expected a stream"),
     }
 }
+
+#[signature(
+sort_by,
+can_block = true,
+output = Passthrough,
+short = "Sort input based on a computed key",
+long = "The key closure is invoked once per row. The columns of the row are exported to the environment using the column names.",
+example = "ls | sort_by {size / 1024}")]
+pub struct SortBy {
+    #[description("the closure used to compute the sort key of each row.")]
+    key: Command,
+    #[description("reverse the sort order.")]
+    #[default(false)]
+    reverse: bool,
+}
+
+fn sort_by(context: CommandContext) -> CrushResult<()> {
+    let cfg: SortBy = SortBy::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let location = context.arguments[0].location;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let base_context = CommandContext {
+                input: empty_channel(),
+                output: black_hole(),
+                arguments: vec![],
+                scope: context.scope.clone(),
+                this: None,
+                global_state: context.global_state.clone(),
+            };
+
+            let output = context.output.initialize(input.types().to_vec())?;
+            let mut res: Vec<(Value, Row)> = Vec::new();
+
+            while let Ok(row) = input.read() {
+                let key = call(cfg.key.copy(), location, &row, input.types(), &base_context)?;
+                let comparable = key.value_type().is_comparable() && match res.first() {
+                    Some((first, _)) => first.partial_cmp(&key).is_some(),
+                    None => true,
+                };
+                if !comparable {
+                    return argument_error(
+                        format!(
+                            "The key of row {} is of type {}, which can't be compared to the other keys",
+                            res.len(),
+                            key.value_type()),
+                        location);
+                }
+                res.push((key, row));
+            }
+
+            if cfg.reverse {
+                res.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+            } else {
+                res.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            }
+
+            for (_, row) in res {
+                output.send(row)?;
+            }
+
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
@@ -0,0 +1,92 @@
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::Passthrough;
+use crate::lang::errors::{error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::pipe::{black_hole, empty_channel};
+use signature::signature;
+use super::r#where::evaluate;
+
+#[signature(
+take_while,
+can_block = true,
+output = Passthrough,
+short = "Pass on rows from io until condition is false",
+long = "The columns of the row are exported to the environment using the column names.",
+long = "No more input is read once the condition is false.",
+example = "seq 100 | take_while {value < 10}")]
+pub struct TakeWhile {
+    #[description("the condition to check.")]
+    condition: Command,
+}
+
+pub fn take_while(context: CommandContext) -> CrushResult<()> {
+    let cfg: TakeWhile = TakeWhile::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let location = context.arguments[0].location;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let base_context = CommandContext {
+                input: empty_channel(),
+                output: black_hole(),
+                arguments: vec![],
+                scope: context.scope.clone(),
+                this: None,
+                global_state: context.global_state.clone(),
+            };
+
+            let output = context.output.initialize(input.types().to_vec())?;
+            while let Ok(row) = input.read() {
+                if !evaluate(cfg.condition.copy(), location, &row, input.types(), &base_context)?
+                    || output.send(row).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
+
+#[signature(
+drop_while,
+can_block = true,
+output = Passthrough,
+short = "Skip rows from io until condition is false, then pass on the rest",
+long = "The columns of the row are exported to the environment using the column names.",
+long = "Once the condition is false, it is not evaluated again.",
+example = "seq 100 | drop_while {value < 90}")]
+pub struct DropWhile {
+    #[description("the condition to check.")]
+    condition: Command,
+}
+
+pub fn drop_while(context: CommandContext) -> CrushResult<()> {
+    let cfg: DropWhile = DropWhile::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let location = context.arguments[0].location;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let base_context = CommandContext {
+                input: empty_channel(),
+                output: black_hole(),
+                arguments: vec![],
+                scope: context.scope.clone(),
+                this: None,
+                global_state: context.global_state.clone(),
+            };
+
+            let output = context.output.initialize(input.types().to_vec())?;
+            let mut dropping = true;
+            while let Ok(row) = input.read() {
+                if dropping {
+                    dropping = evaluate(cfg.condition.copy(), location, &row, input.types(), &base_context)?;
+                }
+                if !dropping && output.send(row).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
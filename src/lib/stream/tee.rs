@@ -0,0 +1,54 @@
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::Passthrough;
+use crate::lang::errors::{error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::pipe::pipe;
+use crate::lang::pretty::create_pretty_printer;
+use signature::signature;
+
+#[signature(
+tee,
+can_block = true,
+output = Passthrough,
+short = "Pass on all rows of input unchanged, while also sending a copy of them to a command",
+long = "The output of the command is printed rather than passed on.",
+example = "ps | tee {count} | where {status != \"Sleeping\"}")]
+pub struct Tee {
+    #[description("the command to send a copy of the input to.")]
+    side: Command,
+}
+
+pub fn tee(context: CommandContext) -> CrushResult<()> {
+    let cfg: Tee = Tee::parse(context.arguments.clone(), &context.global_state.printer())?;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let output = context.output.initialize(input.types().to_vec())?;
+
+            let (side_sender, side_receiver) = pipe();
+            let side_output = side_sender.initialize(input.types().to_vec())?;
+            drop(side_sender);
+            let side = cfg.side;
+            let side_context = CommandContext {
+                input: side_receiver,
+                output: create_pretty_printer(context.global_state.printer().clone(), &context.global_state),
+                arguments: vec![],
+                scope: context.scope.clone(),
+                this: None,
+                global_state: context.global_state.clone(),
+            };
+            context.global_state.threads().spawn(
+                "tee",
+                move || side.invoke(side_context),
+            )?;
+
+            while let Ok(row) = input.read() {
+                // If the side command stops reading its input, keep passing on rows anyway.
+                let _ = side_output.send(row.clone());
+                output.send(row)?;
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
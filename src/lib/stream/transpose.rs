@@ -0,0 +1,43 @@
+use crate::lang::errors::{error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::{ColumnType, Row};
+use crate::lang::value::{Value, ValueType};
+use signature::signature;
+use crate::lang::command::OutputType::Unknown;
+
+#[signature(
+transpose,
+can_block = true,
+short = "Turn the columns of the input into rows",
+long = "The output has one row per input column. The first column, field, holds the name of the original",
+long = "column, and the remaining columns, row0, row1, etc, hold the values of each input row.",
+long = "Because the values of a column may be of different types, all value columns are of type any.",
+long = "The whole input is read into memory before any output is produced.",
+example = "ls | head 3 | transpose",
+output = Unknown)]
+pub struct Transpose {
+}
+
+fn transpose(context: CommandContext) -> CrushResult<()> {
+    Transpose::parse(context.arguments.clone(), &context.global_state.printer())?;
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let mut rows: Vec<Vec<Value>> = Vec::new();
+            while let Ok(row) = input.read() {
+                rows.push(Vec::from(row));
+            }
+
+            let mut types = vec![ColumnType::new("field", ValueType::String)];
+            types.extend((0..rows.len()).map(|idx| ColumnType::new(&format!("row{}", idx), ValueType::Any)));
+            let output = context.output.initialize(types)?;
+
+            for (idx, column) in input.types().iter().enumerate() {
+                let mut cells = vec![Value::string(column.name.clone())];
+                cells.extend(rows.iter().map(|row| row[idx].clone()));
+                output.send(Row::new(cells))?;
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
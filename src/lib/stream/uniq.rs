@@ -1,4 +1,5 @@
-use crate::lang::errors::{error, CrushResult};
+use crate::lang::command::Command;
+use crate::lang::errors::{argument_error, error, CrushResult};
 use crate::lang::execution_context::CommandContext;
 use crate::lang::data::table::ColumnVec;
 use crate::lang::data::table::Row;
@@ -7,6 +8,8 @@ use std::collections::HashSet;
 use signature::signature;
 use crate::lang::command::OutputType::Passthrough;
 use crate::lang::value::Field;
+use crate::lang::pipe::{black_hole, empty_channel};
+use super::r#where::call;
 
 #[signature(
 uniq,
@@ -47,3 +50,52 @@ pub fn uniq(context: CommandContext) -> CrushResult<()> {
         _ => error("Expected io to be a stream"),
     }
 }
+
+#[signature(
+distinct_by,
+can_block = true,
+output = Passthrough,
+short = "Only output the first row if multiple rows has the same computed key",
+long = "The key closure is invoked once per row. The columns of the row are exported to the environment using the column names.",
+example = "ps | distinct_by {user:name}")]
+pub struct DistinctBy {
+    #[description("the closure used to compute the key of each row.")]
+    key: Command,
+}
+
+pub fn distinct_by(context: CommandContext) -> CrushResult<()> {
+    let cfg: DistinctBy = DistinctBy::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let location = context.arguments[0].location;
+
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let base_context = CommandContext {
+                input: empty_channel(),
+                output: black_hole(),
+                arguments: vec![],
+                scope: context.scope.clone(),
+                this: None,
+                global_state: context.global_state.clone(),
+            };
+
+            let output = context.output.initialize(input.types().to_vec())?;
+            let mut seen: HashSet<Value> = HashSet::new();
+            let mut idx = 0;
+            while let Ok(row) = input.read() {
+                let key = call(cfg.key.copy(), location, &row, input.types(), &base_context)?;
+                if !key.value_type().is_hashable() {
+                    return argument_error(
+                        format!("The key of row {} is of type {}, which is not hashable", idx, key.value_type()),
+                        location);
+                }
+                if !seen.contains(&key) {
+                    seen.insert(key);
+                    output.send(row)?;
+                }
+                idx += 1;
+            }
+            Ok(())
+        }
+        _ => error("Expected io to be a stream"),
+    }
+}
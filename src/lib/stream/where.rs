@@ -20,29 +20,52 @@ pub struct Where {
     condition: Command,
 }
 
-fn evaluate(
-    condition: Command,
+/// Invoke the command with the columns of the row as named arguments, and return its output.
+pub(super) fn call(
+    command: Command,
     location: Location,
     row: &Row,
     input_type: &[ColumnType],
     base_context: &CommandContext,
-) -> CrushResult<bool> {
-    let arguments = Vec::from(row.clone())
-        .drain(..)
-        .zip(input_type.iter())
-        .map(|(c, t)| Argument::named(t.name.as_ref(), c, location))
-        .collect();
+) -> CrushResult<Value> {
+    call_with(command, location, vec![], row, input_type, base_context)
+}
+
+/// Like `call`, but with additional arguments passed before the columns of the row.
+pub(super) fn call_with(
+    command: Command,
+    location: Location,
+    mut arguments: Vec<Argument>,
+    row: &Row,
+    input_type: &[ColumnType],
+    base_context: &CommandContext,
+) -> CrushResult<Value> {
+    arguments.extend(
+        Vec::from(row.clone())
+            .drain(..)
+            .zip(input_type.iter())
+            .map(|(c, t)| Argument::named(t.name.as_ref(), c, location)));
 
     let (sender, reciever) = pipe();
 
-    condition.invoke(
+    command.invoke(
         base_context
             .clone()
             .with_args(arguments, None)
             .with_output(sender),
     )?;
 
-    match reciever.recv()? {
+    reciever.recv()
+}
+
+pub(super) fn evaluate(
+    condition: Command,
+    location: Location,
+    row: &Row,
+    input_type: &[ColumnType],
+    base_context: &CommandContext,
+) -> CrushResult<bool> {
+    match call(condition, location, row, input_type, base_context)? {
         Value::Bool(b) => Ok(b),
         _ => error("Expected a boolean result"),
     }
@@ -0,0 +1,113 @@
+use crate::lang::errors::{argument_error_legacy, error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::{ColumnType, Table};
+use crate::lang::{data::table::Row, value::Value, value::ValueType};
+use crate::lang::pipe::OutputStream;
+use signature::signature;
+use std::collections::VecDeque;
+
+#[signature(
+window,
+can_block = true,
+short = "Group consecutive rows of the input into sliding windows",
+long = "Every window is emitted as a single row with a table column named window. A new",
+long = "window is started every step rows. Windows at the end of the input that contain fewer",
+long = "than size rows are dropped unless the partial flag is given.",
+example = "seq 10 | window 3")]
+pub struct Window {
+    #[description("the number of rows in each window.")]
+    size: usize,
+    #[description("the number of rows between the start of each window.")]
+    #[default(1usize)]
+    step: usize,
+    #[description("also emit incomplete windows at the end of the input.")]
+    #[default(false)]
+    partial: bool,
+}
+
+fn send(output: &OutputStream, types: &[ColumnType], rows: &VecDeque<Row>) -> CrushResult<()> {
+    output.send(Row::new(vec![Value::Table(Table::new(
+        types.to_vec(),
+        rows.iter().cloned().collect(),
+    ))]))
+}
+
+fn advance(buffer: &mut VecDeque<Row>, step: usize) -> usize {
+    let n = step.min(buffer.len());
+    buffer.drain(..n);
+    step - n
+}
+
+pub fn window(context: CommandContext) -> CrushResult<()> {
+    let cfg: Window = Window::parse(context.arguments, &context.global_state.printer())?;
+    if cfg.size == 0 || cfg.step == 0 {
+        return argument_error_legacy("Window size and step must be positive");
+    }
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let types = input.types().to_vec();
+            let output = context.output.initialize(vec![
+                ColumnType::new("window", ValueType::Table(types.clone()))])?;
+            let mut buffer = VecDeque::with_capacity(cfg.size);
+            let mut skip = 0;
+            while let Ok(row) = input.read() {
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                buffer.push_back(row);
+                if buffer.len() == cfg.size {
+                    send(&output, &types, &buffer)?;
+                    skip = advance(&mut buffer, cfg.step);
+                }
+            }
+            if cfg.partial {
+                while !buffer.is_empty() {
+                    send(&output, &types, &buffer)?;
+                    advance(&mut buffer, cfg.step);
+                }
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
+
+#[signature(
+chunk,
+can_block = true,
+short = "Group consecutive rows of the input into non-overlapping chunks",
+long = "Every chunk is emitted as a single row with a table column named chunk. The last chunk",
+long = "contains fewer than size rows if the number of input rows is not a multiple of size.",
+example = "seq 10 | chunk 3")]
+pub struct Chunk {
+    #[description("the number of rows in each chunk.")]
+    size: usize,
+}
+
+pub fn chunk(context: CommandContext) -> CrushResult<()> {
+    let cfg: Chunk = Chunk::parse(context.arguments, &context.global_state.printer())?;
+    if cfg.size == 0 {
+        return argument_error_legacy("Chunk size must be positive");
+    }
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            let types = input.types().to_vec();
+            let output = context.output.initialize(vec![
+                ColumnType::new("chunk", ValueType::Table(types.clone()))])?;
+            let mut buffer = VecDeque::with_capacity(cfg.size);
+            while let Ok(row) = input.read() {
+                buffer.push_back(row);
+                if buffer.len() == cfg.size {
+                    send(&output, &types, &buffer)?;
+                    buffer.clear();
+                }
+            }
+            if !buffer.is_empty() {
+                send(&output, &types, &buffer)?;
+            }
+            Ok(())
+        }
+        None => error("Expected a stream"),
+    }
+}
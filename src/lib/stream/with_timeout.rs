@@ -0,0 +1,71 @@
+use crate::lang::command::OutputType::Passthrough;
+use crate::lang::data::table::{ColumnType, Row};
+use crate::lang::errors::{argument_error_legacy, error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::pipe::RecvTimeoutError;
+use crate::lang::value::{Value, ValueType};
+use chrono::Duration;
+use signature::signature;
+
+#[signature(
+with_timeout,
+can_block = true,
+output = Passthrough,
+short = "Pass on the rows of the input, giving up if no row arrives within the specified time.",
+long = "By default, a timeout is an error. With the skip flag, the timeout is ignored and",
+long = "reading continues. With the sentinel flag, a row where every cell is empty is emitted",
+long = "every time the timeout expires, and reading continues. Since any cell may then be empty,",
+long = "all output columns are of type any.",
+example = "http_stream | with_timeout (duration:parse \"5s\") --sentinel",
+)]
+pub struct WithTimeout {
+    #[description("the maximum time to wait for each row.")]
+    timeout: Duration,
+    #[description("keep waiting when the timeout expires.")]
+    #[default(false)]
+    skip: bool,
+    #[description("emit an empty row when the timeout expires.")]
+    #[default(false)]
+    sentinel: bool,
+}
+
+fn with_timeout(context: CommandContext) -> CrushResult<()> {
+    let cfg: WithTimeout = WithTimeout::parse(context.arguments, &context.global_state.printer())?;
+    if cfg.skip && cfg.sentinel {
+        return argument_error_legacy("The skip and sentinel flags are mutually exclusive");
+    }
+    if cfg.timeout < Duration::zero() {
+        return argument_error_legacy("The timeout may not be negative");
+    }
+    match context.input.recv()?.stream() {
+        Some(mut input) => {
+            // Sentinel rows are all empty, so with the sentinel flag every column may be empty
+            let output = if cfg.sentinel {
+                context.output.initialize(
+                    input.types().iter().map(|t| ColumnType::new(&t.name, ValueType::Any)).collect())?
+            } else {
+                context.output.initialize(input.types().to_vec())?
+            };
+            loop {
+                match input.read_timeout(cfg.timeout) {
+                    Ok(row) => output.send(row)?,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => {
+                        if cfg.sentinel {
+                            output.send(Row::new(
+                                input.types().iter().map(|_| Value::Empty()).collect(),
+                            ))?;
+                        } else if !cfg.skip {
+                            return error(format!(
+                                "No input received within {}",
+                                Value::Duration(cfg.timeout).to_string()
+                            ));
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+        None => argument_error_legacy("Expected a stream"),
+    }
+}
@@ -1,25 +1,76 @@
 use crate::lang::errors::CrushResult;
 use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::{ColumnType, Row};
 use crate::lang::pipe::Stream;
+use crate::lang::value::{Value, ValueType};
 use signature::signature;
 
-#[signature(zip, can_block = true, short = "Combine two streams of data into one")]
+#[signature(
+zip,
+can_block = true,
+short = "Combine two streams of data into one",
+long = "By default, the output ends when either stream ends. With --longest, or when a pad value is",
+long = "given, the output continues until both streams have ended, and the cells of the stream that",
+long = "ended first are filled with the pad value. Since the columns then mix real values and padding,",
+long = "all columns are of type any in this mode.",
+example = "zip (seq 3) (seq 5) pad=0")]
 pub struct Zip {
     #[description("the first stream.")]
     first: Stream,
     #[description("the second stream.")]
     second: Stream,
+    #[description("continue until both streams have ended.")]
+    #[default(false)]
+    longest: bool,
+    #[description("the value to fill the cells of the shorter stream with. Implies --longest.")]
+    pad: Option<Value>,
+}
+
+fn padded(types: &[ColumnType]) -> Vec<ColumnType> {
+    types.iter().map(|t| ColumnType::new(&t.name, ValueType::Any)).collect()
 }
 
 pub fn zip(context: CommandContext) -> CrushResult<()> {
     let mut cfg: Zip = Zip::parse(context.arguments, &context.global_state.printer())?;
+    let longest = cfg.longest || cfg.pad.is_some();
     let mut output_type = Vec::new();
-    output_type.append(&mut cfg.first.types().to_vec());
-    output_type.append(&mut cfg.second.types().to_vec());
+    if longest {
+        output_type.append(&mut padded(cfg.first.types()));
+        output_type.append(&mut padded(cfg.second.types()));
+    } else {
+        output_type.append(&mut cfg.first.types().to_vec());
+        output_type.append(&mut cfg.second.types().to_vec());
+    }
     let output = context.output.initialize(output_type)?;
-    while let (Ok(mut row1), Ok(row2)) = (cfg.first.read(), cfg.second.read()) {
-        row1.append(&mut Vec::from(row2));
-        output.send(row1)?;
+
+    if !longest {
+        while let (Ok(mut row1), Ok(row2)) = (cfg.first.read(), cfg.second.read()) {
+            row1.append(&mut Vec::from(row2));
+            output.send(row1)?;
+        }
+        return Ok(());
+    }
+
+    let pad = cfg.pad.unwrap_or(Value::Empty());
+    let first_len = cfg.first.types().len();
+    let second_len = cfg.second.types().len();
+    let mut first_done = false;
+    let mut second_done = false;
+    loop {
+        let row1 = if first_done { None } else { cfg.first.read().ok() };
+        let row2 = if second_done { None } else { cfg.second.read().ok() };
+        first_done = row1.is_none();
+        second_done = row2.is_none();
+        if first_done && second_done {
+            break;
+        }
+        let mut cells = row1
+            .map(Vec::from)
+            .unwrap_or_else(|| vec![pad.clone(); first_len]);
+        cells.append(&mut row2
+            .map(Vec::from)
+            .unwrap_or_else(|| vec![pad.clone(); second_len]));
+        output.send(Row::new(cells))?;
     }
     Ok(())
 }
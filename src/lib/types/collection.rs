@@ -0,0 +1,404 @@
+use crate::lang::command::OutputType::{Known, Unknown};
+use crate::lang::data::list::List;
+use crate::lang::data::table::{ColumnType, Row};
+use crate::lang::errors::{argument_error_legacy, data_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::pipe::Stream;
+use crate::lang::value::{Value, ValueType};
+use signature::signature;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+
+#[signature(
+contains,
+can_block = false,
+output = Known(ValueType::Bool),
+short = "Check if a collection contains the specified element",
+long = "For a list, check if any element is equal to the needle. For a dict, check if the needle",
+long = "is a key. For a struct, check if the needle is the name of a field, including fields of",
+long = "the parents. For a string, check if the needle is a substring.",
+example = "contains (list:of 1 2 3) 2",
+example = "ps | where {contains (list:of \"root\" \"daemon\") user}",
+)]
+struct Contains {
+    #[description("the list, dict, struct or string to search.")]
+    collection: Value,
+    #[description("the element to search for.")]
+    needle: Value,
+}
+
+fn contains(context: CommandContext) -> CrushResult<()> {
+    let cfg: Contains = Contains::parse(context.arguments, &context.global_state.printer())?;
+    let res = match (cfg.collection, cfg.needle) {
+        (Value::List(l), needle) => l.dump().contains(&needle),
+        (Value::Dict(_), needle) if !needle.value_type().is_hashable() => return argument_error_legacy(format!(
+            "Can't look up a {} in a dict, only hashable values can be keys",
+            needle.value_type().to_string()
+        )),
+        (Value::Dict(d), needle) => d.get(&needle).is_some(),
+        (Value::Struct(s), Value::String(name)) => s.get(&name).is_some(),
+        (Value::String(s), Value::String(needle)) => s.contains(needle.as_str()),
+        (collection, needle) => return argument_error_legacy(format!(
+            "Can't check if a {} contains a {}",
+            collection.value_type().to_string(),
+            needle.value_type().to_string()
+        )),
+    };
+    context.output.send(Value::Bool(res))
+}
+
+#[signature(
+len,
+can_block = true,
+output = Known(ValueType::Integer),
+short = "Return the number of elements in a collection",
+long = "For a list, table or dict, this is the number of elements, rows or entries. For a struct,",
+long = "it is the number of fields, not counting fields of the parents unless the all flag is given.",
+long = "For a string, it is the number of characters, and for binary data the number of bytes.",
+long = "",
+long = "If no value is given, the input is used. Materialized collections know their length, but",
+long = "a stream has to be read to the end to count its rows, and can not be read again afterwards.",
+example = "len \"hello\"",
+example = "ls | len",
+)]
+struct Len {
+    #[description("the collection to measure.")]
+    value: Option<Value>,
+    #[description("also count the fields of the parents of a struct.")]
+    #[default(false)]
+    all: bool,
+}
+
+fn len(context: CommandContext) -> CrushResult<()> {
+    let cfg: Len = Len::parse(context.arguments, &context.global_state.printer())?;
+    let value = match cfg.value {
+        Some(value) => value,
+        None => context.input.recv()?,
+    };
+    let res = match value {
+        Value::List(l) => l.len(),
+        Value::Dict(d) => d.len(),
+        Value::Table(t) => t.rows().len(),
+        Value::Struct(s) if cfg.all => s.map().len(),
+        Value::Struct(s) => s.local_elements().len(),
+        Value::String(s) => s.chars().count(),
+        Value::Binary(b) => b.len(),
+        v => match v.stream() {
+            Some(mut input) => {
+                let mut res = 0;
+                while input.read().is_ok() {
+                    res += 1;
+                }
+                res
+            }
+            None => return argument_error_legacy(format!(
+                "Can't take the length of a {}",
+                v.value_type().to_string()
+            )),
+        },
+    };
+    context.output.send(Value::Integer(res as i128))
+}
+
+/// A row of a stream as a single value. Rows with a single column are unwrapped, the same way
+/// the for command does it.
+pub(super) fn row_value(row: Row, types: &[ColumnType]) -> Value {
+    if types.len() == 1 {
+        Vec::from(row).remove(0)
+    } else {
+        Value::Struct(row.into_struct(types))
+    }
+}
+
+fn stream_of(value: Value) -> CrushResult<Stream> {
+    match value.stream() {
+        Some(stream) => Ok(stream),
+        None => argument_error_legacy(format!(
+            "Expected a list or a stream, got a {}",
+            value.value_type().to_string()
+        )),
+    }
+}
+
+/// The element at the specified index, counting from the end if the index is negative.
+fn element_at(value: Value, idx: i128) -> CrushResult<Option<Value>> {
+    if let Value::List(l) = &value {
+        let len = l.len() as i128;
+        let idx = if idx < 0 { len + idx } else { idx };
+        return Ok(if idx >= 0 && idx < len { Some(l.get(idx as usize)?) } else { None });
+    }
+    let mut input = stream_of(value)?;
+    if idx >= 0 {
+        let mut remaining = idx;
+        while let Ok(row) = input.read() {
+            if remaining == 0 {
+                return Ok(Some(row_value(row, input.types())));
+            }
+            remaining -= 1;
+        }
+        Ok(None)
+    } else {
+        // An index too large to negate can never match, since no stream has that many rows
+        let size = match idx.checked_neg().and_then(|n| usize::try_from(n).ok()) {
+            Some(size) => size,
+            None => return Ok(None),
+        };
+        let mut buffer = VecDeque::new();
+        while let Ok(row) = input.read() {
+            if buffer.len() == size {
+                buffer.pop_front();
+            }
+            buffer.push_back(row);
+        }
+        Ok(if buffer.len() == size {
+            buffer.pop_front().map(|row| row_value(row, input.types()))
+        } else {
+            None
+        })
+    }
+}
+
+fn send_element(
+    context: CommandContext,
+    value: Option<Value>,
+    idx: i128,
+    default: Option<Value>,
+) -> CrushResult<()> {
+    let value = match value {
+        Some(value) => value,
+        None => context.input.recv()?,
+    };
+    match (element_at(value, idx)?, default) {
+        (Some(element), _) => context.output.send(element),
+        (None, Some(default)) => context.output.send(default),
+        (None, None) => data_error(format!("No element at index {}", idx)),
+    }
+}
+
+#[signature(
+first,
+can_block = true,
+output = Unknown,
+short = "Return the first element of a list or the first row of a stream",
+long = "If no value is given, the input is used. Only the first row of a stream is read.",
+long = "Rows with a single column are returned as that value, other rows as a struct.",
+example = "first (list:of 1 2 3) # 1",
+example = "ls | first",
+)]
+struct First {
+    #[description("the list or stream.")]
+    value: Option<Value>,
+    #[description("the value to return if there are no elements.")]
+    default: Option<Value>,
+}
+
+fn first(context: CommandContext) -> CrushResult<()> {
+    let cfg: First = First::parse(context.arguments.clone(), &context.global_state.printer())?;
+    send_element(context, cfg.value, 0, cfg.default)
+}
+
+#[signature(
+last,
+can_block = true,
+output = Unknown,
+short = "Return the last element of a list or the last row of a stream",
+long = "If no value is given, the input is used. A stream is read to the end.",
+long = "Rows with a single column are returned as that value, other rows as a struct.",
+example = "last (list:of 1 2 3) # 3",
+example = "ls | last",
+)]
+struct Last {
+    #[description("the list or stream.")]
+    value: Option<Value>,
+    #[description("the value to return if there are no elements.")]
+    default: Option<Value>,
+}
+
+fn last(context: CommandContext) -> CrushResult<()> {
+    let cfg: Last = Last::parse(context.arguments.clone(), &context.global_state.printer())?;
+    send_element(context, cfg.value, -1, cfg.default)
+}
+
+#[signature(
+nth,
+can_block = true,
+output = Unknown,
+short = "Return the element of a list or the row of a stream at the specified index",
+long = "Negative indices count from the end, so -1 is the last element. If no value is given,",
+long = "the input is used. Rows with a single column are returned as that value, other rows",
+long = "as a struct.",
+example = "nth 1 (list:of 1 2 3) # 2",
+example = "ls | nth 3",
+)]
+struct Nth {
+    #[description("the index of the element.")]
+    idx: i128,
+    #[description("the list or stream.")]
+    value: Option<Value>,
+    #[description("the value to return if there is no element at the index.")]
+    default: Option<Value>,
+}
+
+fn nth(context: CommandContext) -> CrushResult<()> {
+    let cfg: Nth = Nth::parse(context.arguments.clone(), &context.global_state.printer())?;
+    send_element(context, cfg.value, cfg.idx, cfg.default)
+}
+
+/// Convert a possibly negative index into an offset into a collection of the specified length,
+/// clamping it to the bounds of the collection.
+fn clamp_index(idx: i128, len: usize) -> usize {
+    let len = len as i128;
+    (if idx < 0 { len + idx } else { idx }).max(0).min(len) as usize
+}
+
+#[signature(
+slice,
+can_block = false,
+output = Unknown,
+short = "Return a part of a list or a string",
+long = "The part starts at the start index and ends just before the end index, or at the end of",
+long = "the value if no end index is given. Negative indices count from the end. Indices outside",
+long = "of the value are clamped to its bounds. Strings are indexed by character.",
+example = "slice (list:of 1 2 3 4) 1 3 # [2, 3]",
+example = "slice \"hello\" (neg 3) # \"llo\"",
+)]
+struct Slice {
+    #[description("the list or string to slice.")]
+    value: Value,
+    #[description("the index of the first element.")]
+    start: i128,
+    #[description("the index after the last element.")]
+    end: Option<i128>,
+}
+
+fn slice(context: CommandContext) -> CrushResult<()> {
+    let cfg: Slice = Slice::parse(context.arguments, &context.global_state.printer())?;
+    let (start, end) = (cfg.start, cfg.end);
+    let range = |len: usize| {
+        let start = clamp_index(start, len);
+        let end = end.map(|end| clamp_index(end, len)).unwrap_or(len);
+        (start, end.max(start))
+    };
+    match cfg.value {
+        Value::List(l) => {
+            let elements = l.dump();
+            let (start, end) = range(elements.len());
+            context.output.send(Value::List(List::new(
+                l.element_type(),
+                elements[start..end].to_vec(),
+            )))
+        }
+        Value::String(s) => {
+            let (start, end) = range(s.chars().count());
+            context.output.send(Value::String(
+                s.chars().skip(start).take(end - start).collect(),
+            ))
+        }
+        v => argument_error_legacy(format!(
+            "Expected a list or a string, got a {}",
+            v.value_type().to_string()
+        )),
+    }
+}
+
+#[signature(
+collect,
+can_block = true,
+output = Unknown,
+short = "Read the input stream into a list",
+long = "If the input has a single column, the list contains the values of that column. Otherwise,",
+long = "every row is converted into a struct. The element type of the list is taken from the",
+long = "column types of the input.",
+example = "seq 5 | collect # [0, 1, 2, 3, 4]",
+example = "ls | collect",
+)]
+struct Collect {}
+
+fn collect(context: CommandContext) -> CrushResult<()> {
+    let mut input = stream_of(context.input.recv()?)?;
+    let types = input.types().to_vec();
+    let element_type = if types.len() == 1 {
+        types[0].cell_type.clone()
+    } else {
+        ValueType::Struct
+    };
+    let mut elements = Vec::new();
+    while let Ok(row) = input.read() {
+        elements.push(row_value(row, &types));
+    }
+    context.output.send(Value::List(List::new(element_type, elements)))
+}
+
+#[signature(
+emit,
+can_block = true,
+output = Unknown,
+short = "Write the elements of a list to the output as a stream",
+long = "A list of structs that all have the same fields becomes a stream with one column per",
+long = "field. Any other list becomes a stream with a single column named value.",
+example = "emit (list:of 1 2 3) | sum",
+example = "ls | collect | emit",
+)]
+struct Emit {
+    #[description("the list to emit.")]
+    list: Value,
+}
+
+/// The columns of a list of structs that all have the same fields, in the same order. A column
+/// where the structs disagree on the type of the value is of type any.
+fn struct_signature(elements: &[Value]) -> Option<Vec<ColumnType>> {
+    let mut res: Option<Vec<ColumnType>> = None;
+    for element in elements {
+        let signature = match element {
+            Value::Struct(s) => s.local_signature(),
+            _ => return None,
+        };
+        match &mut res {
+            None => res = Some(signature),
+            Some(columns) => {
+                if columns.len() != signature.len() {
+                    return None;
+                }
+                for (column, other) in columns.iter_mut().zip(signature) {
+                    if column.name != other.name {
+                        return None;
+                    }
+                    if column.cell_type != other.cell_type {
+                        column.cell_type = ValueType::Any;
+                    }
+                }
+            }
+        }
+    }
+    res
+}
+
+fn emit(context: CommandContext) -> CrushResult<()> {
+    let cfg: Emit = Emit::parse(context.arguments, &context.global_state.printer())?;
+    let list = match cfg.list {
+        Value::List(list) => list,
+        v => return argument_error_legacy(format!(
+            "Expected a list, got a {}",
+            v.value_type().to_string()
+        )),
+    };
+    let elements = list.dump();
+    match struct_signature(&elements) {
+        Some(types) => {
+            let output = context.output.initialize(types)?;
+            for element in elements {
+                if let Value::Struct(s) = element {
+                    output.send(s.to_row())?;
+                }
+            }
+        }
+        None => {
+            let output = context.output.initialize(vec![
+                ColumnType::new("value", list.element_type())])?;
+            for element in elements {
+                output.send(Row::new(vec![element]))?;
+            }
+        }
+    }
+    Ok(())
+}
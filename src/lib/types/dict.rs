@@ -3,6 +3,8 @@ use crate::lang::command::OutputType::{Known, Unknown};
 use crate::lang::command::TypeMap;
 use crate::lang::errors::{argument_error_legacy, CrushResult};
 use crate::lang::execution_context::{ArgumentVector, CommandContext, This};
+use crate::lang::data::list::List;
+use crate::lang::data::table::{ColumnType, Row};
 use crate::lang::value::Value;
 use crate::lang::{data::dict::Dict, value::ValueType};
 use lazy_static::lazy_static;
@@ -23,15 +25,25 @@ lazy_static! {
         Clear::declare_method(&mut res, &path);
         KeyType::declare_method(&mut res, &path);
         ValueTypeMethod::declare_method(&mut res, &path);
+        Keys::declare_method(&mut res, &path);
+        Values::declare_method(&mut res, &path);
+        Entries::declare_method(&mut res, &path);
+        Get::declare_method(&mut res, &path);
+        Set::declare_method(&mut res, &path);
         res.declare(
             full("new"),
             new,
             false,
-            "dict:new",
+            "dict:new [key:any value:any]...",
             "Construct a new dict",
             Some(
-                r#"    Examples:
-    my_dict := (dict string integer):new"#,
+                r#"    The arguments are alternating keys and values to insert into the dict. If no
+    arguments are given and the input is a stream with two columns, the rows of
+    the stream are inserted as key/value pairs.
+
+    Examples:
+    my_dict := (dict string integer):new
+    ages := ((dict string integer):new "Alice" 42 "Bob" 23)"#,
             ),
             Unknown,
             vec![],
@@ -107,22 +119,55 @@ fn __call__(context: CommandContext) -> CrushResult<()> {
     }
 }
 
-fn new(context: CommandContext) -> CrushResult<()> {
-    context.arguments.check_len(0)?;
+fn new(mut context: CommandContext) -> CrushResult<()> {
+    if context.arguments.len() % 2 != 0 {
+        return argument_error_legacy("Expected an even number of arguments");
+    }
     let t = context.this.r#type()?;
     if let ValueType::Dict(key_type, value_type) = t {
         if !key_type.is_hashable() {
-            argument_error_legacy("Key type is not hashable")
+            return argument_error_legacy("Key type is not hashable");
+        }
+        let dict = Dict::new(*key_type, *value_type);
+        if context.arguments.is_empty() {
+            if let Some(mut input) = context.input.recv()?.stream() {
+                if input.types().len() != 2 {
+                    return argument_error_legacy("Expected an input stream with two columns");
+                }
+                while let Ok(row) = input.read() {
+                    let mut cells = Vec::from(row);
+                    let value = cells.remove(1);
+                    let key = cells.remove(0);
+                    insert_checked(&dict, key, value)?;
+                }
+            }
         } else {
-            context
-                .output
-                .send(Value::Dict(Dict::new(*key_type, *value_type)))
+            let mut arguments = context.arguments.drain(..);
+            while let (Some(key), Some(value)) = (arguments.next(), arguments.next()) {
+                if key.argument_type.is_some() || value.argument_type.is_some() {
+                    return argument_error_legacy("Expected only unnamed arguments");
+                }
+                insert_checked(&dict, key.value, value.value)?;
+            }
         }
+        context.output.send(Value::Dict(dict))
     } else {
         argument_error_legacy("Expected a dict type as this value")
     }
 }
 
+fn insert_checked(dict: &Dict, key: Value, value: Value) -> CrushResult<()> {
+    check_key_type(dict, &key)?;
+    if !dict.value_type().is(&value) {
+        return argument_error_legacy(format!(
+            "Invalid value type, expected {}, got {}",
+            dict.value_type().to_string(),
+            value.value_type().to_string()
+        ));
+    }
+    dict.insert(key, value)
+}
+
 fn setitem(mut context: CommandContext) -> CrushResult<()> {
     context.arguments.check_len(2)?;
     let dict = context.this.dict()?;
@@ -239,3 +284,126 @@ fn value_type(context: CommandContext) -> CrushResult<()> {
         .output
         .send(Value::Type(context.this.dict()?.value_type()))
 }
+
+#[signature(
+keys,
+can_block = false,
+output = Unknown,
+short = "A list of all the keys in this dict, in insertion order.",
+)]
+struct Keys {}
+
+fn keys(context: CommandContext) -> CrushResult<()> {
+    context.arguments.check_len(0)?;
+    let d = context.this.dict()?;
+    context.output.send(Value::List(List::new(
+        d.key_type(),
+        d.elements().into_iter().map(|(k, _)| k).collect(),
+    )))
+}
+
+#[signature(
+values,
+can_block = false,
+output = Unknown,
+short = "A list of all the values in this dict, in insertion order.",
+)]
+struct Values {}
+
+fn values(context: CommandContext) -> CrushResult<()> {
+    context.arguments.check_len(0)?;
+    let d = context.this.dict()?;
+    context.output.send(Value::List(List::new(
+        d.value_type(),
+        d.elements().into_iter().map(|(_, v)| v).collect(),
+    )))
+}
+
+#[signature(
+entries,
+can_block = true,
+output = Unknown,
+short = "A stream of all the mappings in this dict, in insertion order.",
+long = "The stream has two columns, key and value.",
+)]
+struct Entries {}
+
+fn entries(context: CommandContext) -> CrushResult<()> {
+    context.arguments.check_len(0)?;
+    let d = context.this.dict()?;
+    let output = context.output.initialize(vec![
+        ColumnType::new("key", d.key_type()),
+        ColumnType::new("value", d.value_type()),
+    ])?;
+    for (k, v) in d.elements() {
+        output.send(Row::new(vec![k, v]))?;
+    }
+    Ok(())
+}
+
+fn check_key_type(dict: &Dict, key: &Value) -> CrushResult<()> {
+    if dict.key_type().is(key) {
+        Ok(())
+    } else {
+        argument_error_legacy(format!(
+            "Invalid key type, expected {}, got {}",
+            dict.key_type().to_string(),
+            key.value_type().to_string()
+        ))
+    }
+}
+
+#[signature(
+get,
+can_block = false,
+output = Unknown,
+short = "Return the value the specified key is mapped to.",
+long = "If the key is not present in the dict, the default value is returned if one was given,",
+long = "otherwise an error is raised.",
+example = "ages:get \"Alice\" default=0",
+)]
+struct Get {
+    #[description("the key to look up.")]
+    key: Value,
+    #[description("the value to return if the key is missing.")]
+    default: Option<Value>,
+}
+
+fn get(context: CommandContext) -> CrushResult<()> {
+    let cfg: Get = Get::parse(context.arguments, &context.global_state.printer())?;
+    let d = context.this.dict()?;
+    check_key_type(&d, &cfg.key)?;
+    match (d.get(&cfg.key), cfg.default) {
+        (Some(value), _) => context.output.send(value),
+        (None, Some(default)) => context.output.send(default),
+        (None, None) => argument_error_legacy(format!("Unknown key {}", cfg.key.to_string())),
+    }
+}
+
+#[signature(
+set,
+can_block = false,
+output = Unknown,
+short = "Return a dict with the specified key mapped to the specified value.",
+long = "By default, a modified copy of the dict is returned and the original is left untouched.",
+long = "If the mutate flag is given, the dict is modified in place and returned.",
+example = "ages := (ages:set \"Alice\" 42)",
+)]
+struct Set {
+    #[description("the key to map.")]
+    key: Value,
+    #[description("the value to map the key to.")]
+    value: Value,
+    #[description("modify this dict instead of returning a copy.")]
+    #[default(false)]
+    mutate: bool,
+}
+
+fn set(context: CommandContext) -> CrushResult<()> {
+    let cfg: Set = Set::parse(context.arguments, &context.global_state.printer())?;
+    let d = context.this.dict()?;
+    check_key_type(&d, &cfg.key)?;
+    let d = if cfg.mutate { d } else { d.copy() };
+    d.insert(cfg.key, cfg.value)?;
+    context.output.send(Value::Dict(d))
+}
@@ -9,6 +9,7 @@ use chrono::Duration;
 use lazy_static::lazy_static;
 use ordered_map::OrderedMap;
 use signature::signature;
+use crate::util::time::parse_duration;
 
 fn full(name: &'static str) -> Vec<&'static str> {
     vec!["global", "types", "duration", name]
@@ -51,6 +52,7 @@ lazy_static! {
             vec![],
             );
         Of::declare_method(&mut res, &path);
+        Parse::declare_method(&mut res, &path);
         res.declare(
             full("__neg__"), neg, false,
             "neg duration",
@@ -130,6 +132,25 @@ fn of(context: CommandContext) -> CrushResult<()> {
     context.output.send(Value::Duration(res))
 }
 
+#[signature(
+parse,
+can_block = false,
+output = Known(ValueType::Duration),
+short = "Parse a duration string",
+long = "The string consists of one or more numbers, each followed by a unit. Valid units are",
+long = "ns, us, ms, s, m, h, d and w. A number without a unit is a number of seconds.",
+example = "duration:parse \"1h30m\"",
+example = "duration:parse \"500ms\"")]
+struct Parse {
+    #[description("the duration string to parse.")]
+    duration: String,
+}
+
+fn parse(context: CommandContext) -> CrushResult<()> {
+    let cfg: Parse = Parse::parse(context.arguments, &context.global_state.printer())?;
+    context.output.send(Value::Duration(parse_duration(&cfg.duration)?))
+}
+
 fn neg(context: CommandContext) -> CrushResult<()> {
     context.arguments.check_len(0)?;
     context
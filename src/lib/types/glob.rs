@@ -1,7 +1,7 @@
 use crate::lang::command::Command;
 use crate::lang::command::OutputType::Known;
 use crate::lang::command::TypeMap;
-use crate::lang::errors::CrushResult;
+use crate::lang::errors::{argument_error_legacy, CrushResult};
 use crate::lang::execution_context::{ArgumentVector, This};
 use crate::lang::data::list::List;
 use crate::lang::value::ValueType;
@@ -10,6 +10,8 @@ use crate::util::file::cwd;
 use crate::util::glob::Glob;
 use lazy_static::lazy_static;
 use ordered_map::OrderedMap;
+use signature::signature;
+use crate::lang::data::table::{ColumnType, Row};
 
 fn full(name: &'static str) -> Vec<&'static str> {
     vec!["global", "types", "glob", name]
@@ -18,6 +20,7 @@ fn full(name: &'static str) -> Vec<&'static str> {
 lazy_static! {
     pub static ref METHODS: OrderedMap<String, Command> = {
         let mut res: OrderedMap<String, Command> = OrderedMap::new();
+        let path = vec!["global", "types", "glob"];
         res.declare(
             full("new"),
             new,
@@ -58,8 +61,12 @@ lazy_static! {
             Known(ValueType::List(Box::from(ValueType::File))),
             vec![],
         );
+        Expand::declare_method(&mut res, &path);
         res
     };
+    static ref EXPAND_OUTPUT_TYPE: Vec<ColumnType> = vec![
+        ColumnType::new("file", ValueType::File),
+    ];
 }
 
 fn new(mut context: CommandContext) -> CrushResult<()> {
@@ -83,8 +90,49 @@ fn files(context: CommandContext) -> CrushResult<()> {
     let g = context.this.glob()?;
     let mut files = Vec::new();
     g.glob_files(&cwd()?, &mut files)?;
+    files.sort();
     context.output.send(Value::List(List::new(
         ValueType::File,
         files.drain(..).map(|f| Value::File(f)).collect(),
     )))
 }
+
+#[signature(
+    expand,
+    can_block = true,
+    output = Known(ValueType::TableInputStream(EXPAND_OUTPUT_TYPE.clone())),
+    short = "Return a sorted stream of all files matching a pattern",
+    long = "The pattern is a traditional shell style pattern, where * matches any sequence of",
+    long = "characters except /, ** matches any sequence of characters and ? matches a single",
+    long = "character. If no pattern is given, this glob is used instead. A pattern that does",
+    long = "not match any files results in an empty stream.",
+    example = "glob:expand \"src/**.rs\"",
+)]
+struct Expand {
+    #[description("the shell style pattern to expand.")]
+    pattern: Option<String>,
+    #[description("follow symbolic links to directories.")]
+    #[default(false)]
+    follow: bool,
+}
+
+fn expand(context: CommandContext) -> CrushResult<()> {
+    let cfg: Expand = Expand::parse(context.arguments, &context.global_state.printer())?;
+    let g = match (cfg.pattern, context.this) {
+        (Some(pattern), _) => Glob::from_shell_pattern(&pattern),
+        (None, Some(Value::Glob(g))) => g,
+        _ => return argument_error_legacy("Expected a pattern"),
+    };
+    let mut files = Vec::new();
+    if cfg.follow {
+        g.glob_files_following_symlinks(&cwd()?, &mut files)?;
+    } else {
+        g.glob_files(&cwd()?, &mut files)?;
+    }
+    files.sort();
+    let output = context.output.initialize(EXPAND_OUTPUT_TYPE.clone())?;
+    for file in files {
+        output.send(Row::new(vec![Value::File(file)]))?;
+    }
+    Ok(())
+}
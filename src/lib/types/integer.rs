@@ -1,5 +1,5 @@
 use crate::lang::command::Command;
-use crate::lang::command::OutputType::Known;
+use crate::lang::command::OutputType::{Known, Unknown};
 use crate::lang::command::TypeMap;
 use crate::lang::errors::{argument_error_legacy, CrushResult};
 use crate::lang::execution_context::{ArgumentVector, This};
@@ -7,6 +7,8 @@ use crate::lang::value::ValueType;
 use crate::lang::{execution_context::CommandContext, value::Value};
 use lazy_static::lazy_static;
 use ordered_map::OrderedMap;
+use chrono::Duration;
+use std::convert::TryFrom;
 
 fn full(name: &'static str) -> Vec<&'static str> {
     vec!["global", "types", "integer", name]
@@ -39,10 +41,10 @@ lazy_static! {
             full("__mul__"),
             mul,
             false,
-            "integer * factor:(integer|float)",
+            "integer * factor:(integer|float|duration)",
             "Multiply this number with the specified factor",
             None,
-            Known(ValueType::Integer),
+            Unknown,
             vec![],
         );
         res.declare(
@@ -109,16 +111,42 @@ binary_op!(
     Float,
     |a, b| a as f64 - b
 );
-binary_op!(
-    mul,
-    integer,
-    Integer,
-    Integer,
-    |a, b| a * b,
-    Float,
-    Float,
-    |a, b| a as f64 * b
-);
+fn mul_duration(factor: i128, duration: Duration) -> CrushResult<Duration> {
+    let factor = match i32::try_from(factor) {
+        Ok(f) => f as i128,
+        Err(_) => return argument_error_legacy("Duration multiplier out of range"),
+    };
+    let seconds = duration.num_seconds();
+    let nanos = (duration - Duration::seconds(seconds))
+        .num_nanoseconds()
+        .unwrap_or(0) as i128
+        * factor;
+    let seconds = seconds as i128 * factor + nanos.div_euclid(1_000_000_000);
+    let nanos = nanos.rem_euclid(1_000_000_000) as i64;
+    if seconds < Duration::min_value().num_seconds() as i128
+        || seconds > Duration::max_value().num_seconds() as i128
+    {
+        return argument_error_legacy("Duration overflow");
+    }
+    match Duration::seconds(seconds as i64).checked_add(&Duration::nanoseconds(nanos)) {
+        Some(d) => Ok(d),
+        None => argument_error_legacy("Duration overflow"),
+    }
+}
+
+fn mul(mut context: CommandContext) -> CrushResult<()> {
+    context.arguments.check_len(1)?;
+    let this = context.this.integer()?;
+    match context.arguments.value(0)? {
+        Value::Integer(v) => context.output.send(Value::Integer(this * v)),
+        Value::Float(v) => context.output.send(Value::Float(this as f64 * v)),
+        Value::Duration(v) => context.output.send(Value::Duration(mul_duration(this, v)?)),
+        other => argument_error_legacy(format!(
+            "Incompatible argument type for arithmetic operation: {}",
+            other.value_type().to_string(),
+        )),
+    }
+}
 binary_op!(
     div,
     integer,
@@ -3,11 +3,15 @@ use crate::lang::command::OutputType::Unknown;
 use crate::lang::command::TypeMap;
 use crate::lang::errors::{argument_error_legacy, data_error, mandate, CrushResult};
 use crate::lang::execution_context::{ArgumentVector, CommandContext, This};
+use crate::lang::argument::Argument;
+use crate::lang::ast::Location;
+use crate::lang::pipe::{empty_channel, pipe};
 use crate::lang::value::Value;
 use crate::lang::{command::Command, data::list::List, value::ValueType};
 use lazy_static::lazy_static;
 use ordered_map::OrderedMap;
 use signature::signature;
+use std::cmp::Ordering;
 
 fn full(name: &'static str) -> Vec<&'static str> {
     vec!["global", "types", "list", name]
@@ -135,7 +139,30 @@ lazy_static! {
             vec![],
         );
         Repeat::declare_method(&mut res, &path);
+        Range::declare_method(&mut res, &path);
         Call::declare_method(&mut res, &path);
+        Join::declare_method(&mut res, &path);
+        Map::declare_method(&mut res, &path);
+        Filter::declare_method(&mut res, &path);
+        Sort::declare_method(&mut res, &path);
+        Reverse::declare_method(&mut res, &path);
+        res.declare(
+            full("reduce"),
+            reduce,
+            true,
+            "list:reduce [initial:any] reducer:command",
+            "Combine all elements of the list into a single value",
+            Some(r#"    The reducer is called once per element with two arguments, the accumulated
+    value so far and the element. Its output becomes the new accumulated value.
+    If no initial value is given, the first element of the list is used as the
+    initial value, and it is an error to reduce an empty list.
+
+    Example:
+
+    (list:of 1 2 3):reduce 0 {|acc elem| acc + elem}"#),
+            Unknown,
+            vec![],
+        );
 
         res
     };
@@ -164,6 +191,71 @@ fn repeat(context: CommandContext) -> CrushResult<()> {
         .send(Value::List(List::new(cfg.item.value_type(), l)))
 }
 
+#[signature(
+    range,
+    can_block = false,
+    output = Known(ValueType::List(Box::from(ValueType::Integer))),
+    short = "Create a list of sequential integers",
+    long = "The end of the range is exclusive, just like in the seq command. If the step",
+    long = "points away from the end, start and end are swapped.",
+    example = "list:range 0 10 2 # [0, 2, 4, 6, 8]",
+)]
+struct Range {
+    #[description("the first integer of the range.")]
+    from: i128,
+    #[description("the end of the range.")]
+    to: i128,
+    #[description("the difference between successive integers.")]
+    #[default(1)]
+    step: i128,
+}
+
+fn range(context: CommandContext) -> CrushResult<()> {
+    let mut cfg: Range = Range::parse(context.arguments, &context.global_state.printer())?;
+    if cfg.step == 0 {
+        return argument_error_legacy("The step of a range can not be zero");
+    }
+    if (cfg.to > cfg.from) != (cfg.step > 0) {
+        std::mem::swap(&mut cfg.from, &mut cfg.to);
+    }
+
+    let mut l = Vec::new();
+    let mut idx = cfg.from;
+    while (cfg.step > 0 && idx < cfg.to) || (cfg.step < 0 && idx > cfg.to) {
+        l.push(Value::Integer(idx));
+        idx += cfg.step;
+    }
+    context
+        .output
+        .send(Value::List(List::new(ValueType::Integer, l)))
+}
+
+#[signature(
+join,
+can_block = false,
+output = Known(ValueType::String),
+short = "Join the elements of this list into a string, using the specified separator.",
+long = "Elements that are not strings are converted to their string representation.",
+example = "(list:of 1 2 3):join \",\" # 1,2,3",
+)]
+struct Join {
+    #[description("the separator to put between elements.")]
+    #[default("")]
+    separator: String,
+}
+
+fn join(context: CommandContext) -> CrushResult<()> {
+    let cfg: Join = Join::parse(context.arguments, &context.global_state.printer())?;
+    let list = context.this.list()?;
+    context.output.send(Value::String(
+        list.dump()
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(&cfg.separator),
+    ))
+}
+
 #[signature(
 __call__,
 can_block = false,
@@ -358,3 +450,211 @@ fn getitem(mut context: CommandContext) -> CrushResult<()> {
     let idx = context.arguments.integer(0)?;
     context.output.send(list.get(idx as usize)?)
 }
+
+/// Invoke a closure with the specified values as unnamed arguments and return its output.
+fn call(
+    command: &Command,
+    arguments: Vec<Value>,
+    location: Location,
+    context: &CommandContext,
+) -> CrushResult<Value> {
+    let (sender, receiver) = pipe();
+    command.invoke(CommandContext {
+        input: empty_channel(),
+        output: sender,
+        arguments: arguments
+            .into_iter()
+            .map(|v| Argument::unnamed(v, location))
+            .collect(),
+        scope: context.scope.clone(),
+        this: None,
+        global_state: context.global_state.clone(),
+    })?;
+    receiver.recv()
+}
+
+/// The common type of all the specified values, or any if they differ.
+fn common_type(values: &[Value]) -> ValueType {
+    let mut types = values.iter().map(|v| v.value_type());
+    match types.next() {
+        Some(first) => {
+            if types.all(|t| t == first) {
+                first
+            } else {
+                ValueType::Any
+            }
+        }
+        None => ValueType::Any,
+    }
+}
+
+#[signature(
+map,
+can_block = true,
+output = Unknown,
+short = "Create a new list by calling the specified closure once for every element.",
+long = "The element is passed to the closure as its only argument. If all output values",
+long = "are of the same type, the new list will be of that type, otherwise of type any.",
+example = "(list:of 1 2 3):map {|x| x * 2} # [2, 4, 6]",
+)]
+struct Map {
+    #[description("the closure to call for each element.")]
+    closure: Command,
+}
+
+fn map(context: CommandContext) -> CrushResult<()> {
+    let location = context
+        .arguments
+        .get(0)
+        .map(|a| a.location)
+        .unwrap_or(Location::new(0, 0));
+    let cfg: Map = Map::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let list = context.this.clone().list()?;
+    let mut res = Vec::with_capacity(list.len());
+    for element in list.dump() {
+        res.push(call(&cfg.closure, vec![element], location, &context)?);
+    }
+    context
+        .output
+        .send(Value::List(List::new(common_type(&res), res)))
+}
+
+#[signature(
+filter,
+can_block = true,
+output = Unknown,
+short = "Create a new list containing the elements for which the specified closure returns true.",
+long = "The element is passed to the closure as its only argument.",
+example = "(list:of 1 2 3 4):filter {|x| (x:mod 2) == 0} # [2, 4]",
+)]
+struct Filter {
+    #[description("the condition to filter on.")]
+    condition: Command,
+}
+
+fn filter(context: CommandContext) -> CrushResult<()> {
+    let location = context
+        .arguments
+        .get(0)
+        .map(|a| a.location)
+        .unwrap_or(Location::new(0, 0));
+    let cfg: Filter = Filter::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let list = context.this.clone().list()?;
+    let mut res = Vec::new();
+    for element in list.dump() {
+        match call(&cfg.condition, vec![element.clone()], location, &context)? {
+            Value::Bool(true) => res.push(element),
+            Value::Bool(false) => {}
+            v => {
+                return argument_error_legacy(format!(
+                    "Expected the condition to return a bool, got a {}",
+                    v.value_type().to_string()
+                ))
+            }
+        }
+    }
+    context
+        .output
+        .send(Value::List(List::new(list.element_type(), res)))
+}
+
+fn reduce(mut context: CommandContext) -> CrushResult<()> {
+    context.arguments.check_len_range(1, 2)?;
+    let location = context.arguments[0].location;
+    let (initial, reducer) = if context.arguments.len() == 2 {
+        (Some(context.arguments.value(0)?), context.arguments.command(1)?)
+    } else {
+        (None, context.arguments.command(0)?)
+    };
+    let mut elements = context.this.clone().list()?.dump().into_iter();
+    let mut acc = match initial {
+        Some(v) => v,
+        None => mandate(elements.next(), "Can't reduce an empty list without an initial value")?,
+    };
+    for element in elements {
+        acc = call(&reducer, vec![acc, element], location, &context)?;
+    }
+    context.output.send(acc)
+}
+
+#[signature(
+sort,
+can_block = true,
+output = Unknown,
+short = "Sort the elements of this list.",
+long = "By default, a sorted copy of the list is returned and the original is left untouched.",
+long = "If a key closure is given, it is called once per element and elements are ordered",
+long = "by its output instead of by the elements themselves.",
+example = "(list:of 3 1 2):sort --reverse # [3, 2, 1]",
+)]
+struct Sort {
+    #[description("a closure mapping each element to the key to sort on.")]
+    key: Option<Command>,
+    #[description("sort in descending order.")]
+    #[default(false)]
+    reverse: bool,
+    #[description("sort this list in place instead of returning a copy.")]
+    #[default(false)]
+    mutate: bool,
+}
+
+fn sort(context: CommandContext) -> CrushResult<()> {
+    let location = context
+        .arguments
+        .get(0)
+        .map(|a| a.location)
+        .unwrap_or(Location::new(0, 0));
+    let cfg: Sort = Sort::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let list = context.this.clone().list()?;
+    let mut entries = Vec::with_capacity(list.len());
+    for element in list.dump() {
+        let key = match &cfg.key {
+            Some(key) => call(key, vec![element.clone()], location, &context)?,
+            None => element.clone(),
+        };
+        entries.push((key, element));
+    }
+
+    let mut incomparable = false;
+    let reverse = cfg.reverse;
+    entries.sort_by(|a, b| match a.0.partial_cmp(&b.0) {
+        Some(ordering) if reverse => ordering.reverse(),
+        Some(ordering) => ordering,
+        None => {
+            incomparable = true;
+            Ordering::Equal
+        }
+    });
+    if incomparable {
+        return argument_error_legacy("Tried to sort a list containing values that can't be compared");
+    }
+    let mut sorted = entries.into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+    if cfg.mutate {
+        list.clear();
+        list.append(&mut sorted)?;
+        context.output.send(Value::List(list))
+    } else {
+        context
+            .output
+            .send(Value::List(List::new(list.element_type(), sorted)))
+    }
+}
+
+#[signature(
+reverse,
+can_block = false,
+output = Unknown,
+short = "Return a copy of this list with the elements in reverse order.",
+example = "(list:of 1 2 3):reverse # [3, 2, 1]",
+)]
+struct Reverse {}
+
+fn reverse(context: CommandContext) -> CrushResult<()> {
+    context.arguments.check_len(0)?;
+    let list = context.this.list()?;
+    let mut elements = list.dump();
+    elements.reverse();
+    context
+        .output
+        .send(Value::List(List::new(list.element_type(), elements)))
+}
@@ -1,17 +1,19 @@
 use crate::lang::argument::column_names;
 use crate::lang::command::CrushCommand;
 use crate::lang::command::OutputType::{Known, Unknown};
-use crate::lang::errors::{mandate, CrushResult};
+use crate::lang::errors::{argument_error_legacy, mandate, CrushResult};
 use crate::lang::execution_context::ArgumentVector;
 use crate::lang::execution_context::{CommandContext, This};
 use crate::lang::data::scope::Scope;
 use crate::lang::pipe::black_hole;
+use crate::lang::data::list::List;
 use crate::lang::data::table::ColumnType;
 use crate::lang::value::ValueType;
 use crate::lang::{data::r#struct::Struct, value::Value};
 use crate::lang::ordered_string_map::OrderedStringMap;
 use signature::signature;
 pub mod binary;
+mod collection;
 pub mod dict;
 pub mod duration;
 pub mod file;
@@ -19,9 +21,11 @@ pub mod float;
 pub mod glob;
 pub mod integer;
 pub mod list;
+mod number;
 pub mod re;
 pub mod scope;
 pub mod string;
+pub mod r#struct;
 pub mod table;
 pub mod table_input_stream;
 pub mod table_output_stream;
@@ -31,12 +35,34 @@ pub mod time;
 materialize,
 can_block = true,
 short = "Recursively convert all streams in io to materialized form",
-example= "ls | materialize"
+long = "Materialized values can be iterated over any number of times, e.g. by storing them in",
+long = "a variable.",
+example= "ls | materialize",
+example= "seq 5 | materialize --list",
 )]
-struct Materialize {}
+struct Materialize {
+    #[description("convert a stream with a single column into a list instead of a table.")]
+    #[default(false)]
+    list: bool,
+}
 
 fn materialize(context: CommandContext) -> CrushResult<()> {
-    context.output.send(context.input.recv()?.materialize()?)
+    let cfg: Materialize = Materialize::parse(context.arguments, &context.global_state.printer())?;
+    let value = context.input.recv()?;
+    if cfg.list {
+        if let Some(mut input) = value.stream() {
+            if input.types().len() != 1 {
+                return argument_error_legacy("Expected a stream with exactly one column");
+            }
+            let element_type = input.types()[0].cell_type.clone();
+            let mut elements = Vec::new();
+            while let Ok(row) = input.read() {
+                elements.push(Vec::from(row).remove(0).materialize()?);
+            }
+            return context.output.send(Value::List(List::new(element_type, elements)));
+        }
+    }
+    context.output.send(value.materialize()?)
 }
 
 fn new(mut context: CommandContext) -> CrushResult<()> {
@@ -126,6 +152,23 @@ pub fn convert(context: CommandContext) -> CrushResult<()> {
     context.output.send(cfg.value.convert(cfg.target_type)?)
 }
 
+#[signature(
+to_string,
+can_block = false,
+output = Known(ValueType::String),
+short = "Convert the value to a string",
+example = "to_string 1_000 # \"1000\""
+)]
+struct ToStringSignature {
+    #[description("the value to convert.")]
+    value: Value,
+}
+
+fn to_string(context: CommandContext) -> CrushResult<()> {
+    let cfg: ToStringSignature = ToStringSignature::parse(context.arguments, &context.global_state.printer())?;
+    context.output.send(Value::String(cfg.value.to_string()))
+}
+
 #[signature(
 __typeof__,
 can_block = false,
@@ -142,6 +185,24 @@ pub fn __typeof__(context: CommandContext) -> CrushResult<()> {
     context.output.send(Value::Type(cfg.value.value_type()))
 }
 
+#[signature(
+is_empty,
+can_block = false,
+output = Known(ValueType::Bool),
+short = "True if the value is empty.",
+long = "Only the empty value itself is considered empty, e.g. an empty list or string is not.",
+example = "is_empty (cond:coalesce)",
+)]
+struct IsEmpty {
+    #[description("the value to check.")]
+    value: Value,
+}
+
+pub fn is_empty(context: CommandContext) -> CrushResult<()> {
+    let cfg: IsEmpty = IsEmpty::parse(context.arguments, &context.global_state.printer())?;
+    context.output.send(Value::Bool(cfg.value == Value::Empty()))
+}
+
 fn class_set(mut context: CommandContext) -> CrushResult<()> {
     let this = context.this.r#struct()?;
     let value = context.arguments.value(1)?;
@@ -208,8 +269,35 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
             Data::declare(env)?;
             Class::declare(env)?;
             Convert::declare(env)?;
+            ToStringSignature::declare(env)?;
+            env.declare_command(
+                "format", string::format::format_command, false,
+                "format pattern:string [parameters:any]...",
+                "Format arguments into a string",
+                Some(r#"    Every {} in the pattern is replaced by the next parameter. Parameters
+    can also be referred to by position, e.g. {0}, or by name, e.g. {name}.
+    It is an error for the number of {} placeholders to differ from the
+    number of parameters.
+
+    Example:
+
+    format "{} = {}" key value"#),
+                Known(ValueType::String),
+                vec![],
+            )?;
             TypeOf::declare(env)?;
+            IsEmpty::declare(env)?;
+            collection::Contains::declare(env)?;
+            collection::Len::declare(env)?;
+            collection::First::declare(env)?;
+            collection::Last::declare(env)?;
+            collection::Nth::declare(env)?;
+            collection::Slice::declare(env)?;
+            collection::Collect::declare(env)?;
+            collection::Emit::declare(env)?;
             Materialize::declare(env)?;
+            number::ToInt::declare(env)?;
+            number::ToFloat::declare(env)?;
 
             env.declare("file", Value::Type(ValueType::File))?;
             env.declare("type", Value::Type(ValueType::Type))?;
@@ -0,0 +1,128 @@
+use crate::lang::errors::{argument_error_legacy, data_error, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::data::table::{ColumnVec, Row};
+use crate::lang::value::{Value, ValueType};
+use crate::lang::command::OutputType::Unknown;
+use signature::signature;
+
+/// Parse a single value using the specified parser. Strings are parsed, integers are widened
+/// to floats, values that already have the target type are passed through and anything else
+/// is an error. If parsing fails
+/// and a default is given, the default is returned instead.
+fn parse_value(
+    value: Value,
+    target: &ValueType,
+    default: &Option<Value>,
+    parser: &impl Fn(&str) -> Option<Value>,
+) -> CrushResult<Value> {
+    let parsed = match &value {
+        Value::String(s) => parser(s.trim()),
+        Value::Integer(i) if *target == ValueType::Float => Some(Value::Float(*i as f64)),
+        v if v.value_type() == *target => Some(value.clone()),
+        _ => None,
+    };
+    match (parsed, default) {
+        (Some(v), _) => Ok(v),
+        (None, Some(d)) => Ok(d.clone()),
+        (None, None) => data_error(format!("Can't convert \"{}\" to {}", value, target)),
+    }
+}
+
+/// Run a conversion command. If the value is a field, the corresponding column of the input
+/// stream is converted. If the value is missing, the input is converted; a single column
+/// stream is converted column-wise and any other value is converted directly.
+fn convert(
+    context: CommandContext,
+    value: Option<Value>,
+    target: ValueType,
+    default: Option<Value>,
+    parser: impl Fn(&str) -> Option<Value>,
+) -> CrushResult<()> {
+    let (input, column) = match value {
+        Some(Value::Field(f)) => (context.input.recv()?, Some(f)),
+        Some(v) => return context.output.send(parse_value(v, &target, &default, &parser)?),
+        None => (context.input.recv()?, None),
+    };
+
+    match input.stream() {
+        Some(mut stream) => {
+            let idx = match column {
+                Some(c) => stream.types().find(&c)?,
+                None if stream.types().len() == 1 => 0,
+                None => return argument_error_legacy(
+                    "Specify which column to convert when the input has more than one column"),
+            };
+            let mut types = stream.types().to_vec();
+            types[idx].cell_type = match &default {
+                Some(d) if d.value_type() != target => ValueType::Any,
+                _ => target.clone(),
+            };
+            let output = context.output.initialize(types)?;
+            while let Ok(row) = stream.read() {
+                let mut cells = Vec::from(row);
+                let cell = std::mem::replace(&mut cells[idx], Value::Empty());
+                cells[idx] = parse_value(cell, &target, &default, &parser)?;
+                output.send(Row::new(cells))?;
+            }
+            Ok(())
+        }
+        None => match column {
+            Some(_) => argument_error_legacy("Expected a stream when converting a column"),
+            None => context.output.send(parse_value(input, &target, &default, &parser)?),
+        },
+    }
+}
+
+#[signature(
+to_int,
+can_block = true,
+output = Unknown,
+short = "Parse strings into integers",
+long = "If a field is given, that column of the input stream is converted. If no value is given,",
+long = "the input is converted, either as a single value or as a stream with a single column.",
+example = "to_int \"ff\" radix=16",
+example = "csv:from ./people.csv | to_int ^age default=0")]
+pub struct ToInt {
+    #[description("the string to parse, or the column of the input to convert.")]
+    value: Option<Value>,
+    #[description("the base of the number.")]
+    #[default(10u32)]
+    radix: u32,
+    #[description("the value to use instead of failing if a string can't be parsed.")]
+    default: Option<Value>,
+}
+
+fn to_int(context: CommandContext) -> CrushResult<()> {
+    let cfg: ToInt = ToInt::parse(context.arguments, &context.global_state.printer())?;
+    if cfg.radix < 2 || cfg.radix > 36 {
+        return argument_error_legacy("The radix must be between 2 and 36");
+    }
+    let radix = cfg.radix;
+    convert(context, cfg.value, ValueType::Integer, cfg.default, move |s| {
+        i128::from_str_radix(&s.replace('_', ""), radix).ok().map(Value::Integer)
+    })
+}
+
+#[signature(
+to_float,
+can_block = true,
+output = Unknown,
+short = "Parse strings into floating point numbers",
+long = "If a field is given, that column of the input stream is converted. If no value is given,",
+long = "the input is converted, either as a single value or as a stream with a single column.",
+long = "Integers are converted to floating point numbers as well.",
+example = "to_float \"3.14\"",
+example = "csv:from ./measurements.csv | to_float ^temperature")]
+pub struct ToFloat {
+    #[description("the string to parse, or the column of the input to convert.")]
+    value: Option<Value>,
+    #[description("the value to use instead of failing if a string can't be parsed.")]
+    default: Option<Value>,
+}
+
+fn to_float(context: CommandContext) -> CrushResult<()> {
+    let cfg: ToFloat = ToFloat::parse(context.arguments, &context.global_state.printer())?;
+    convert(context, cfg.value, ValueType::Float, cfg.default, |s| {
+        s.replace('_', "").parse::<f64>().ok().map(Value::Float)
+    })
+}
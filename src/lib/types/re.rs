@@ -1,9 +1,11 @@
 use crate::lang::command::Command;
-use crate::lang::command::OutputType::Known;
+use crate::lang::command::OutputType::{Known, Unknown};
 use crate::lang::command::TypeMap;
 use crate::lang::errors::{argument_error_legacy, CrushResult};
 use crate::lang::execution_context::{ArgumentVector, This};
 use crate::lang::value::ValueType;
+use crate::lang::data::list::List;
+use crate::lang::data::r#struct::Struct;
 use crate::lang::{execution_context::CommandContext, value::Value};
 use lazy_static::lazy_static;
 use ordered_map::OrderedMap;
@@ -18,16 +20,7 @@ lazy_static! {
     pub static ref METHODS: OrderedMap<String, Command> = {
         let mut res: OrderedMap<String, Command> = OrderedMap::new();
         let path = vec!["global", "types", "re"];
-        res.declare(
-            full("match"),
-            r#match,
-            false,
-            "re =~ io:string",
-            "True if the io matches the pattern",
-            None,
-            Known(ValueType::Bool),
-            vec![],
-        );
+        Match::declare_method(&mut res, &path);
         res.declare(
             full("not_match"),
             not_match,
@@ -63,10 +56,57 @@ fn new(mut context: CommandContext) -> CrushResult<()> {
     context.output.send(res)
 }
 
-fn r#match(mut context: CommandContext) -> CrushResult<()> {
+#[signature(
+    r#match,
+    can_block = false,
+    output = Unknown,
+    short = "True if the needle matches the pattern",
+    long = "The =~ operator is a shorthand for this method. If the captures flag is given,",
+    long = "the capture groups of the first match are returned instead. If the pattern",
+    long = "contains named groups, the result is a struct of the named groups, otherwise it",
+    long = "is a list of all groups, where the first element is the whole match. If there",
+    long = "is no match, the empty value is returned.",
+    example = "re\"(?P<key>[a-z]+)=(?P<value>[0-9]+)\":match \"a=1\" --captures",
+)]
+struct Match {
+    #[description("the string to match against.")]
+    needle: String,
+    #[description("return the capture groups of the first match.")]
+    #[default(false)]
+    captures: bool,
+}
+
+fn r#match(context: CommandContext) -> CrushResult<()> {
     let re = context.this.re()?.1;
-    let needle = context.arguments.string(0)?;
-    context.output.send(Value::Bool(re.is_match(&needle)))
+    let cfg: Match = Match::parse(context.arguments, &context.global_state.printer())?;
+    if !cfg.captures {
+        return context.output.send(Value::Bool(re.is_match(&cfg.needle)));
+    }
+    context.output.send(match re.captures(&cfg.needle) {
+        None => Value::Empty(),
+        Some(captures) => {
+            if re.capture_names().any(|name| name.is_some()) {
+                Value::Struct(Struct::new(
+                    re.capture_names()
+                        .flatten()
+                        .map(|name| (
+                            name,
+                            Value::string(captures.name(name).map(|m| m.as_str()).unwrap_or("")),
+                        ))
+                        .collect(),
+                    None,
+                ))
+            } else {
+                Value::List(List::new(
+                    ValueType::String,
+                    captures
+                        .iter()
+                        .map(|m| Value::string(m.map(|m| m.as_str()).unwrap_or("")))
+                        .collect(),
+                ))
+            }
+        }
+    })
 }
 
 fn not_match(mut context: CommandContext) -> CrushResult<()> {
@@ -1,5 +1,5 @@
 use crate::lang::errors::{argument_error_legacy, mandate, CrushResult};
-use crate::lang::execution_context::{CommandContext, This};
+use crate::lang::execution_context::{ArgumentVector, CommandContext, This};
 use crate::lang::{argument::Argument, value::Value};
 use crate::lib::types::string::format::FormatState::*;
 
@@ -27,8 +27,11 @@ fn argument_by_name<'a>(name: &str, param: &'a [Argument]) -> Option<&'a Argumen
     None
 }
 
-fn do_format(format: &str, param: Vec<Argument>) -> CrushResult<String> {
+/// Format the parameters according to the format string. If strict is true, it is an error for
+/// the number of implicit placeholders to differ from the number of parameters.
+fn do_format(format: &str, param: Vec<Argument>, strict: bool) -> CrushResult<String> {
     let mut implicit_idx = 0;
+    let mut explicit = false;
     let mut res = String::new();
     let mut state = Normal;
     for ch in format.chars() {
@@ -60,8 +63,14 @@ fn do_format(format: &str, param: Vec<Argument>) -> CrushResult<String> {
                     implicit_idx += 1;
                     Normal
                 }
-                '0'..='9' => Index(ch.to_digit(10).unwrap() as usize),
-                'a'..='z' | 'A'..='Z' => Name(ch.to_string()),
+                '0'..='9' => {
+                    explicit = true;
+                    Index(ch.to_digit(10).unwrap() as usize)
+                }
+                'a'..='z' | 'A'..='Z' => {
+                    explicit = true;
+                    Name(ch.to_string())
+                }
                 _ => return argument_error_legacy("Invalid format string"),
             },
 
@@ -83,6 +92,13 @@ fn do_format(format: &str, param: Vec<Argument>) -> CrushResult<String> {
             },
         }
     }
+    if strict && !explicit && implicit_idx != param.len() {
+        return argument_error_legacy(format!(
+            "Format string has {} placeholders, but {} arguments were given",
+            implicit_idx,
+            param.len()
+        ));
+    }
     Ok(res)
 }
 
@@ -90,5 +106,14 @@ pub fn format(context: CommandContext) -> CrushResult<()> {
     let format = context.this.string()?;
     context
         .output
-        .send(Value::String(do_format(&format, context.arguments)?))
+        .send(Value::String(do_format(&format, context.arguments, false)?))
+}
+
+pub fn format_command(mut context: CommandContext) -> CrushResult<()> {
+    context.arguments.check_len_min(1)?;
+    let format = context.arguments.string(0)?;
+    context.arguments.remove(0);
+    context
+        .output
+        .send(Value::String(do_format(&format, context.arguments, true)?))
 }
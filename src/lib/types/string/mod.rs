@@ -1,19 +1,22 @@
 use crate::lang::command::Command;
-use crate::lang::command::OutputType::Known;
+use crate::lang::command::OutputType::{Known, Unknown};
 use crate::lang::command::TypeMap;
-use crate::lang::errors::{argument_error_legacy, CrushResult};
+use crate::lang::errors::{argument_error_legacy, data_error, mandate, to_crush_error, CrushResult};
 use crate::lang::execution_context::{ArgumentVector, This};
-use crate::lang::value::Value;
+use crate::lang::value::{Field, Value};
+use crate::lang::data::table::{ColumnType, ColumnVec, Row};
 use crate::lang::{execution_context::CommandContext, data::list::List, value::ValueType};
 use lazy_static::lazy_static;
 use ordered_map::OrderedMap;
 use signature::signature;
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
 
 fn full(name: &'static str) -> Vec<&'static str> {
     vec!["global", "types", "string", name]
 }
 
-mod format;
+pub mod format;
 
 lazy_static! {
     pub static ref METHODS: OrderedMap<String, Command> =
@@ -36,10 +39,12 @@ lazy_static! {
                 vec![],
             );
             Join::declare_method(&mut res, &path);
+            Replace::declare_method(&mut res, &path);
             LPad::declare_method(&mut res, &path);
             RPad::declare_method(&mut res, &path);
             StartsWith::declare_method(&mut res, &path);
             EndsWith::declare_method(&mut res, &path);
+            Contains::declare_method(&mut res, &path);
             IsAlphanumeric::declare_method(&mut res, &path);
             IsAlphabetic::declare_method(&mut res, &path);
             IsAscii::declare_method(&mut res, &path);
@@ -49,6 +54,9 @@ lazy_static! {
             IsControl::declare_method(&mut res, &path);
             Len::declare_method(&mut res, &path);
             IsDigit::declare_method(&mut res, &path);
+            Reverse::declare_method(&mut res, &path);
+            Pad::declare_method(&mut res, &path);
+            Find::declare_method(&mut res, &path);
             res
         };
 }
@@ -65,28 +73,51 @@ fn len(context: CommandContext) -> CrushResult<()> {
         .send(Value::Integer(context.this.string()?.len() as i128))
 }
 
+/// Apply a transformation to this string. If this is not a string (e.g. when the method is
+/// called on the string type itself), the transformation is instead applied to every row
+/// of the input, which must be a stream with a single column of strings.
+fn map_string(context: CommandContext, f: impl Fn(&str) -> String) -> CrushResult<()> {
+    match context.this {
+        Some(Value::String(s)) => context.output.send(Value::String(f(&s))),
+        _ => {
+            let mut input = mandate(context.input.recv()?.stream(), "Expected a stream")?;
+            if input.types().len() != 1 || input.types()[0].cell_type != ValueType::String {
+                return data_error(
+                    "Expected an input stream containing a single column of type string",
+                );
+            }
+            let output = context.output.initialize(input.types().to_vec())?;
+            while let Ok(row) = input.read() {
+                match Vec::from(row).remove(0) {
+                    Value::String(s) => output.send(Row::new(vec![Value::String(f(&s))]))?,
+                    _ => return data_error("Expected a string"),
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
 #[signature(
-    upper, can_block=false, output=Known(ValueType::String),
-    short="Returns an identical string but in upper case")]
+    upper, can_block=true, output=Unknown,
+    short="Returns an identical string but in upper case",
+    example="lines:from some_file.txt | string:upper")]
 struct Upper {}
 
 fn upper(context: CommandContext) -> CrushResult<()> {
     context.arguments.check_len(0)?;
-    context
-        .output
-        .send(Value::String(context.this.string()?.to_uppercase()))
+    map_string(context, |s| s.to_uppercase())
 }
 
 #[signature(
-    lower, can_block=false, output=Known(ValueType::String),
-    short="Returns an identical string but in lower case")]
+    lower, can_block=true, output=Unknown,
+    short="Returns an identical string but in lower case",
+    example="lines:from some_file.txt | string:lower")]
 struct Lower {}
 
 fn lower(context: CommandContext) -> CrushResult<()> {
     context.arguments.check_len(0)?;
-    context
-        .output
-        .send(Value::String(context.this.string()?.to_lowercase()))
+    map_string(context, |s| s.to_lowercase())
 }
 
 #[signature(
@@ -94,30 +125,63 @@ split,
 can_block = false,
 output=Known(ValueType::List(Box::from(ValueType::String))),
 short = "Splits a string using the specified separator",
+long = "If the separator is the empty string, the string is split into individual characters.",
+example = "\"a,b,c\":split \",\" limit=1 # [a, \"b,c\"]",
+example = "\"a1b22c\":split \"[0-9]+\" --regex # [a, b, c]",
 )]
 struct Split {
     #[description("the separator to split on.")]
     separator: String,
+    #[description("the maximum number of splits to perform.")]
+    limit: Option<usize>,
+    #[description("treat the separator as a regular expression.")]
+    #[default(false)]
+    regex: bool,
 }
 
-fn split(mut context: CommandContext) -> CrushResult<()> {
+fn split(context: CommandContext) -> CrushResult<()> {
     let cfg: Split = Split::parse(context.arguments, &context.global_state.printer())?;
     let this = context.this.string()?;
+    let max = cfg.limit.map(|l| l.saturating_add(1)).unwrap_or(usize::MAX);
+
+    let res: Vec<Value> = if cfg.regex {
+        let re = to_crush_error(Regex::new(&cfg.separator))?;
+        re.splitn(&this, max).map(Value::string).collect()
+    } else if cfg.separator.is_empty() {
+        let mut res = Vec::new();
+        for (idx, ch) in this.char_indices() {
+            if res.len() + 1 == max {
+                res.push(Value::string(&this[idx..]));
+                break;
+            }
+            res.push(Value::from(ch));
+        }
+        res
+    } else {
+        this.splitn(max, cfg.separator.as_str()).map(Value::string).collect()
+    };
 
-    context.output.send(Value::List(List::new(
-        ValueType::String,
-        this.split(&cfg.separator).map(|s| Value::string(s)).collect(),
-    )))
+    context.output.send(Value::List(List::new(ValueType::String, res)))
 }
 
 #[signature(
-trim, can_block=false, output=Known(ValueType::String),
-short="Returns a string with all whitespace trimmed from both ends")]
-struct Trim {}
+trim, can_block=true, output=Unknown,
+short="Returns a string with all whitespace trimmed from both ends",
+long="If a set of characters is given, those characters are trimmed instead of whitespace.",
+example="\"--abc--\":trim \"-\" # abc",
+example="lines:from some_file.txt | string:trim")]
+struct Trim {
+    #[description("the characters to trim.")]
+    characters: Option<String>,
+}
 
 fn trim(context: CommandContext) -> CrushResult<()> {
-    context.arguments.check_len(0)?;
-    context.output.send(Value::string(context.this.string()?.trim()))
+    let cfg: Trim = Trim::parse(context.arguments.clone(), &context.global_state.printer())?;
+    match cfg.characters {
+        Some(characters) =>
+            map_string(context, |s| s.trim_matches(|c: char| characters.contains(c)).to_string()),
+        None => map_string(context, |s| s.trim().to_string()),
+    }
 }
 
 #[signature(
@@ -151,6 +215,48 @@ fn join(context: CommandContext) -> CrushResult<()> {
     context.output.send(Value::String(res))
 }
 
+#[signature(
+replace,
+can_block = true,
+output = Unknown,
+short = "Returns a string where occurrences of the pattern have been replaced",
+long = "In regex mode, the replacement may refer to capture groups using $1, $2, etc.",
+example = "\"a-b-c\":replace \"-\" \"+\" count=1 # a+b-c",
+example = "\"abc123\":replace \"([a-z]+)([0-9]+)\" \"$2$1\" --regex # 123abc",
+example = "lines:from some_file.txt | string:replace \"foo\" \"bar\"",
+)]
+struct Replace {
+    #[description("the substring to replace.")]
+    pattern: String,
+    #[description("the replacement.")]
+    replacement: String,
+    #[description("treat the pattern as a regular expression.")]
+    #[default(false)]
+    regex: bool,
+    #[description("the maximum number of replacements to make. If unspecified, all occurrences are replaced.")]
+    count: Option<usize>,
+}
+
+fn replace(context: CommandContext) -> CrushResult<()> {
+    let cfg: Replace = Replace::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let pattern = cfg.pattern;
+    let replacement = cfg.replacement;
+    match (cfg.regex, cfg.count) {
+        (_, Some(0)) => map_string(context, |s| s.to_string()),
+        (true, count) => {
+            let re = to_crush_error(Regex::new(&pattern))?;
+            map_string(
+                context,
+                |s| re.replacen(s, count.unwrap_or(0), replacement.as_str()).to_string(),
+            )
+        }
+        (false, count) => map_string(
+            context,
+            |s| s.replacen(pattern.as_str(), &replacement, count.unwrap_or(usize::MAX)),
+        ),
+    }
+}
+
 #[signature(
     lpad,
     can_block = false,
@@ -226,36 +332,124 @@ fn repeat(mut context: CommandContext) -> CrushResult<()> {
     context.output.send(Value::string(s.repeat(cfg.times).as_str()))
 }
 
+/// Apply a predicate to this string. If this is not a string, the predicate is instead applied
+/// to every row of the input, using the specified column, or the only column if none was given,
+/// and the output is a stream with a single boolean column.
+fn test_string(
+    context: CommandContext,
+    field: Option<Field>,
+    f: impl Fn(&str) -> bool,
+) -> CrushResult<()> {
+    match context.this {
+        Some(Value::String(s)) => context.output.send(Value::Bool(f(&s))),
+        _ => {
+            let mut input = mandate(context.input.recv()?.stream(), "Expected a stream")?;
+            let idx = match field {
+                Some(field) => input.types().find(&field)?,
+                None if input.types().len() == 1 => 0,
+                None => return argument_error_legacy("Missing column to test"),
+            };
+            if input.types()[idx].cell_type != ValueType::String {
+                return data_error("Expected a column of type string");
+            }
+            let output = context.output.initialize(vec![
+                ColumnType::new(&input.types()[idx].name, ValueType::Bool)])?;
+            while let Ok(row) = input.read() {
+                match &row.cells()[idx] {
+                    Value::String(s) => output.send(Row::new(vec![Value::Bool(f(s))]))?,
+                    _ => return data_error("Expected a string"),
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Lower case the string if ignore_case is set, so that it can be compared case insensitively.
+fn fold_case(s: &str, ignore_case: bool) -> String {
+    if ignore_case {
+        s.to_lowercase()
+    } else {
+        s.to_string()
+    }
+}
+
 #[signature(
-ends_with, can_block = false,
-output=Known(ValueType::Bool),
+ends_with, can_block = true,
+output=Unknown,
 short = "True if this string ends with the specified suffix",
+long = "When called on the string type, the check is done for every row of the input stream.",
+long = "The output then has a single boolean column, named like the checked column. All other",
+long = "columns of the input are dropped.",
+example = "ls | select ^file | string:ends_with \".rs\"",
 )]
 struct EndsWith {
     #[description("suffix to check for")]
     suffix: String,
+    #[description("ignore case when comparing.")]
+    #[default(false)]
+    ignore_case: bool,
+    #[description("the column to check if called on a stream.")]
+    field: Option<Field>,
 }
 
-fn ends_with(mut context: CommandContext) -> CrushResult<()> {
-    let cfg: EndsWith = EndsWith::parse(context.arguments, &context.global_state.printer())?;
-    let s = context.this.string()?;
-    context.output.send(Value::Bool(s.ends_with(&cfg.suffix)))
+fn ends_with(context: CommandContext) -> CrushResult<()> {
+    let cfg: EndsWith = EndsWith::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let ignore_case = cfg.ignore_case;
+    let suffix = fold_case(&cfg.suffix, ignore_case);
+    test_string(context, cfg.field, |s| fold_case(s, ignore_case).ends_with(&suffix))
 }
 
 #[signature(
-starts_with, can_block = false,
-output=Known(ValueType::Bool),
+starts_with, can_block = true,
+output=Unknown,
 short = "True if this string starts with the specified prefix",
+long = "When called on the string type, the check is done for every row of the input stream.",
+long = "The output then has a single boolean column, named like the checked column. All other",
+long = "columns of the input are dropped.",
+example = "lines:from example_data/age.csv | string:starts_with \"a\"",
 )]
 struct StartsWith {
     #[description("prefix to check for")]
     prefix: String,
+    #[description("ignore case when comparing.")]
+    #[default(false)]
+    ignore_case: bool,
+    #[description("the column to check if called on a stream.")]
+    field: Option<Field>,
 }
 
-fn starts_with(mut context: CommandContext) -> CrushResult<()> {
-    let cfg: StartsWith = StartsWith::parse(context.arguments, &context.global_state.printer())?;
-    let s = context.this.string()?;
-    context.output.send(Value::Bool(s.starts_with(&cfg.prefix)))
+fn starts_with(context: CommandContext) -> CrushResult<()> {
+    let cfg: StartsWith = StartsWith::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let ignore_case = cfg.ignore_case;
+    let prefix = fold_case(&cfg.prefix, ignore_case);
+    test_string(context, cfg.field, |s| fold_case(s, ignore_case).starts_with(&prefix))
+}
+
+#[signature(
+contains, can_block = true,
+output=Unknown,
+short = "True if this string contains the specified substring",
+long = "When called on the string type, the check is done for every row of the input stream.",
+long = "The output then has a single boolean column, named like the checked column. All other",
+long = "columns of the input are dropped.",
+example = "\"Hello, world\":contains \"WORLD\" --ignore_case",
+)]
+struct Contains {
+    #[description("substring to check for")]
+    needle: String,
+    #[description("ignore case when comparing.")]
+    #[default(false)]
+    ignore_case: bool,
+    #[description("the column to check if called on a stream.")]
+    field: Option<Field>,
+}
+
+fn contains(context: CommandContext) -> CrushResult<()> {
+    let cfg: Contains = Contains::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let ignore_case = cfg.ignore_case;
+    let needle = fold_case(&cfg.needle, ignore_case);
+    test_string(context, cfg.field, |s| fold_case(s, ignore_case).contains(&needle))
 }
 
 macro_rules! per_char_method {
@@ -340,3 +534,117 @@ fn is_digit(context: CommandContext) -> CrushResult<()> {
         s.chars().all(|ch| ch.is_digit(cfg.radix as u32)),
     ))
 }
+
+#[signature(
+    reverse, can_block=true, output=Unknown,
+    short="Returns the string with its characters in reverse order",
+    long="By default, the string is reversed one unicode scalar value at a time, which separates",
+    long="combining marks from the character they modify. With the graphemes flag, the string is",
+    long="reversed one extended grapheme cluster, i.e. one user-perceived character, at a time.",
+    example="\"abc\":reverse # \"cba\"")]
+struct Reverse {
+    #[description("keep combining characters together with the character they modify.")]
+    #[default(false)]
+    graphemes: bool,
+}
+
+fn reverse_graphemes(s: &str) -> String {
+    s.graphemes(true).rev().collect()
+}
+
+fn reverse(context: CommandContext) -> CrushResult<()> {
+    let cfg: Reverse = Reverse::parse(context.arguments.clone(), &context.global_state.printer())?;
+    if cfg.graphemes {
+        map_string(context, reverse_graphemes)
+    } else {
+        map_string(context, |s| s.chars().rev().collect())
+    }
+}
+
+#[signature(
+    pad, can_block=true, output=Unknown,
+    short="Returns the string padded to be at least the specified width",
+    long="The string is aligned to the left unless --right or --center is given. Strings that are",
+    long="already at least width wide are returned unchanged. The width is measured in unicode",
+    long="scalar values, or with the graphemes flag, in extended grapheme clusters as in the reverse method.",
+    example="\"abc\":pad 7 --center fill=\"*\" # \"**abc**\"")]
+struct Pad {
+    #[description("the minimum width of the result.")]
+    width: usize,
+    #[description("align the string to the left (the default).")]
+    #[default(false)]
+    left: bool,
+    #[description("align the string to the right.")]
+    #[default(false)]
+    right: bool,
+    #[description("center the string, with any odd fill character on the right.")]
+    #[default(false)]
+    center: bool,
+    #[description("the character to pad with.")]
+    #[default(' ')]
+    fill: char,
+    #[description("measure the width in graphemes instead of unicode scalar values.")]
+    #[default(false)]
+    graphemes: bool,
+}
+
+fn pad(context: CommandContext) -> CrushResult<()> {
+    let cfg: Pad = Pad::parse(context.arguments.clone(), &context.global_state.printer())?;
+    if [cfg.left, cfg.right, cfg.center].iter().filter(|b| **b).count() > 1 {
+        return argument_error_legacy("Only one of left, right and center may be specified");
+    }
+    map_string(context, |s| {
+        let len = if cfg.graphemes {
+            s.graphemes(true).count()
+        } else {
+            s.chars().count()
+        };
+        let missing = cfg.width.saturating_sub(len);
+        let (before, after) = if cfg.right {
+            (missing, 0)
+        } else if cfg.center {
+            (missing / 2, missing - missing / 2)
+        } else {
+            (0, missing)
+        };
+        let fill = cfg.fill.to_string();
+        format!("{}{}{}", fill.repeat(before), s, fill.repeat(after))
+    })
+}
+
+#[signature(
+    find, can_block=false, output=Unknown,
+    short="Returns the index of the first occurrence of the needle in this string",
+    long="The index is counted in unicode scalar values, like the indices used by slice. If the",
+    long="needle is not found, empty is returned. Only matches starting at or after the from index are",
+    long="considered. With the last flag, the index of the last such match is returned instead.",
+    example="\"banana\":find \"an\" --last # 3")]
+struct Find {
+    #[description("the substring to search for.")]
+    needle: String,
+    #[description("the index to start searching from.")]
+    #[default(0usize)]
+    from: usize,
+    #[description("return the last match instead of the first.")]
+    #[default(false)]
+    last: bool,
+}
+
+fn find(context: CommandContext) -> CrushResult<()> {
+    let cfg: Find = Find::parse(context.arguments.clone(), &context.global_state.printer())?;
+    let s = context.this.string()?;
+    let start = match s.char_indices().nth(cfg.from) {
+        Some((idx, _)) => idx,
+        None => s.len(),
+    };
+    let haystack = &s[start..];
+    let found = if cfg.last {
+        haystack.rfind(&cfg.needle)
+    } else {
+        haystack.find(&cfg.needle)
+    };
+    context.output.send(match found {
+        Some(idx) => Value::Integer((s[..start + idx].chars().count()) as i128),
+        None => Value::Empty(),
+    })
+}
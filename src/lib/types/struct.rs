@@ -0,0 +1,208 @@
+use crate::lang::command::Command;
+use crate::lang::command::OutputType::{Known, Unknown};
+use crate::lang::data::list::List;
+use crate::lang::data::r#struct::{Struct, StructReader};
+use crate::lang::data::table::{ColumnType, Row};
+use crate::lang::errors::{argument_error, argument_error_legacy, CrushResult};
+use crate::lang::execution_context::CommandContext;
+use crate::lang::ordered_string_map::OrderedStringMap;
+use crate::lang::pipe::CrushStream;
+use crate::lang::value::{Value, ValueType};
+use crate::util::identity_arc::Identity;
+use lazy_static::lazy_static;
+use ordered_map::OrderedMap;
+use signature::signature;
+
+lazy_static! {
+    pub static ref METHODS: OrderedMap<String, Command> = {
+        let mut res: OrderedMap<String, Command> = OrderedMap::new();
+        let path = vec!["global", "types", "struct"];
+        New::declare_method(&mut res, &path);
+        SetParent::declare_method(&mut res, &path);
+        Parent::declare_method(&mut res, &path);
+        Fields::declare_method(&mut res, &path);
+        Signature::declare_method(&mut res, &path);
+        Enumerate::declare_method(&mut res, &path);
+        res
+    };
+}
+
+#[signature(
+new,
+can_block = false,
+output = Known(ValueType::Struct),
+short = "Construct a struct with the specified members",
+long = "Unlike data, only named members are accepted. If a parent is given, fields that are",
+long = "missing from the new struct are looked up in the parent.",
+example = "point := (struct:new x=1 y=2)",
+example = "point3d := (struct:new z=3 parent=point)",
+)]
+struct New {
+    #[description("the struct to look up missing fields in.")]
+    parent: Option<Struct>,
+    #[named()]
+    #[description("the members of the struct.")]
+    fields: OrderedStringMap<Value>,
+}
+
+fn new(context: CommandContext) -> CrushResult<()> {
+    if let Some(arg) = context.arguments.iter().find(|a| a.argument_type.is_none()) {
+        return argument_error("Struct members must be named", arg.location);
+    }
+    let cfg: New = New::parse(context.arguments, &context.global_state.printer())?;
+    context.output.send(Value::Struct(Struct::new(
+        cfg.fields.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        cfg.parent,
+    )))
+}
+
+#[signature(
+set_parent,
+can_block = false,
+output = Known(ValueType::Empty),
+short = "Set the parent of a struct",
+long = "Fields that are missing from the struct are looked up in the parent. If no parent is",
+long = "given, the struct is detached from its current parent.",
+example = "struct:set_parent point3d point",
+)]
+struct SetParent {
+    #[description("the struct to modify.")]
+    child: Struct,
+    #[description("the new parent.")]
+    parent: Option<Struct>,
+}
+
+fn set_parent(context: CommandContext) -> CrushResult<()> {
+    let cfg: SetParent = SetParent::parse(context.arguments, &context.global_state.printer())?;
+    let mut current = cfg.parent.clone();
+    while let Some(s) = current {
+        if s.id() == cfg.child.id() {
+            return argument_error_legacy("A struct can not be its own ancestor");
+        }
+        current = s.parent();
+    }
+    cfg.child.set_parent(cfg.parent);
+    context.output.send(Value::Empty())
+}
+
+#[signature(
+parent,
+can_block = false,
+output = Unknown,
+short = "Return the parent of a struct, or empty if it has none",
+example = "struct:parent point3d",
+)]
+struct Parent {
+    #[description("the struct to return the parent of.")]
+    child: Struct,
+}
+
+fn parent(context: CommandContext) -> CrushResult<()> {
+    let cfg: Parent = Parent::parse(context.arguments, &context.global_state.printer())?;
+    context.output.send(match cfg.child.parent() {
+        Some(p) => Value::Struct(p),
+        None => Value::Empty(),
+    })
+}
+
+fn elements(s: &Struct, all: bool) -> Vec<(String, Value)> {
+    if all {
+        s.map()
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    } else {
+        s.local_elements()
+    }
+}
+
+#[signature(
+fields,
+can_block = false,
+output = Known(ValueType::List(Box::from(ValueType::String))),
+short = "Return the names of the fields of a struct, in the order they were added",
+long = "Only the fields of the struct itself are returned, unless the all flag is given, in which",
+long = "case the fields of the parents are included as well.",
+example = "struct:fields (struct:new x=1 y=2)",
+)]
+struct Fields {
+    #[description("the struct to list the fields of.")]
+    value: Struct,
+    #[description("also include the fields of the parents.")]
+    #[default(false)]
+    all: bool,
+}
+
+fn fields(context: CommandContext) -> CrushResult<()> {
+    let cfg: Fields = Fields::parse(context.arguments, &context.global_state.printer())?;
+    context.output.send(Value::List(List::new(
+        ValueType::String,
+        elements(&cfg.value, cfg.all)
+            .into_iter()
+            .map(|(name, _)| Value::String(name))
+            .collect(),
+    )))
+}
+
+#[signature(
+signature,
+can_block = false,
+short = "Return a stream with the name and type of every field of a struct",
+long = "Only the fields of the struct itself are returned, unless the all flag is given, in which",
+long = "case the fields of the parents are included as well.",
+example = "struct:signature (struct:new x=1 y=\"a\")",
+)]
+struct Signature {
+    #[description("the struct to describe.")]
+    value: Struct,
+    #[description("also include the fields of the parents.")]
+    #[default(false)]
+    all: bool,
+}
+
+fn signature(context: CommandContext) -> CrushResult<()> {
+    let cfg: Signature = Signature::parse(context.arguments, &context.global_state.printer())?;
+    let columns = if cfg.all {
+        elements(&cfg.value, true)
+            .into_iter()
+            .map(|(name, value)| ColumnType::new(&name, value.value_type()))
+            .collect()
+    } else {
+        cfg.value.local_signature()
+    };
+    let output = context.output.initialize(vec![
+        ColumnType::new("name", ValueType::String),
+        ColumnType::new("type", ValueType::Type),
+    ])?;
+    for column in columns {
+        output.send(Row::new(vec![
+            Value::String(column.name),
+            Value::Type(column.cell_type),
+        ]))?;
+    }
+    Ok(())
+}
+
+#[signature(
+enumerate,
+can_block = true,
+output = Unknown,
+short = "Return a stream of the fields of a struct, together with the position of each field",
+long = "The columns are idx, name and value. The fields of the struct itself come first, in the",
+long = "order they were added, followed by the fields of the parents.",
+example = "struct:enumerate (struct:new x=1 y=2)",
+)]
+struct Enumerate {
+    #[description("the struct to list the fields of.")]
+    value: Struct,
+}
+
+fn enumerate(context: CommandContext) -> CrushResult<()> {
+    let cfg: Enumerate = Enumerate::parse(context.arguments, &context.global_state.printer())?;
+    let mut reader = StructReader::indexed(cfg.value);
+    let output = context.output.initialize(reader.types().to_vec())?;
+    while let Ok(row) = reader.read() {
+        output.send(row)?;
+    }
+    Ok(())
+}
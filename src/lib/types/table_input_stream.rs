@@ -1,15 +1,16 @@
 use crate::lang::command::Command;
-use crate::lang::command::OutputType::Known;
+use crate::lang::command::OutputType::{Known, Unknown};
 use crate::lang::errors::{argument_error_legacy, CrushResult};
 use crate::lang::execution_context::{This};
 use crate::lang::value::ValueType;
 use crate::lang::{execution_context::CommandContext, value::Value};
 use crate::lib::types::{column_types};
+use crate::lib::types::collection::row_value;
 use lazy_static::lazy_static;
 use ordered_map::OrderedMap;
 use signature::signature;
 use crate::lang::ordered_string_map::OrderedStringMap;
-use crate::lang::pipe::streams;
+use crate::lang::pipe::{bounded_streams, DEFAULT_STREAM_CAPACITY};
 use crate::lang::data::r#struct::Struct;
 use crate::lang::command::CrushCommand;
 
@@ -31,6 +32,7 @@ lazy_static! {
         Call::declare_method(&mut res, &path);
         GetItem::declare_method(&mut res, &path);
         Pipe::declare_method(&mut res, &path);
+        Pull::declare_method(&mut res, &path);
         res
     };
 }
@@ -89,14 +91,23 @@ pipe,
 can_block = false,
 output = Known(ValueType::Struct),
 short = "Returns a struct containing a read end and a write end of a pipe of the specified type",
-example = "pipe := ((table_input_stream value=integer):pipe)\n    _1 := (seq 100_000 | pipe:output:write | bg)\n    sum_job_id := (pipe:input | sum | bg)\n    pipe:close\n    sum_job_id | fg"
+example = "pipe := ((table_input_stream value=integer):pipe)\n    _1 := (seq 100_000 | pipe:output:write | bg)\n    sum_job_id := (pipe:input | sum | bg)\n    pipe:close\n    sum_job_id | fg",
+example = "pipe := ((table_input_stream value=any):pipe)\n    pipe:output:push 42\n    pipe:input:pull"
 )]
-struct Pipe {}
+struct Pipe {
+    #[description("the number of rows the pipe can hold before writing to it blocks.")]
+    capacity: Option<usize>,
+}
 
 fn pipe(context: CommandContext) -> CrushResult<()> {
+    let cfg: Pipe = Pipe::parse(context.arguments, &context.global_state.printer())?;
     match context.this.r#type()? {
         ValueType::TableInputStream(subtype) => {
-            let (output, input) = streams(subtype);
+            let capacity = cfg.capacity.unwrap_or(DEFAULT_STREAM_CAPACITY);
+            if capacity == 0 {
+                return argument_error_legacy("The capacity of a pipe must be at least one");
+            }
+            let (output, input) = bounded_streams(subtype, capacity);
             context.output.send(Value::Struct(Struct::new(
                 vec![
                     ("input", Value::TableInputStream(input)),
@@ -116,3 +127,28 @@ fn close(context: CommandContext) -> CrushResult<()> {
     pipe.set("output", Value::Empty());
     Ok(())
 }
+
+#[signature(
+pull,
+can_block = true,
+output = Unknown,
+short = "Read a single row from this stream",
+long = "Blocks until a row is available. Rows with a single column are returned as that value,",
+long = "other rows as a struct. Once every write end of the stream has been closed and all rows",
+long = "have been read, the default value is returned if one was given, otherwise an error.",
+example = "pipe:input:pull default=0",
+)]
+struct Pull {
+    #[description("the value to return once the stream has no more rows.")]
+    default: Option<Value>,
+}
+
+fn pull(context: CommandContext) -> CrushResult<()> {
+    let cfg: Pull = Pull::parse(context.arguments, &context.global_state.printer())?;
+    let input = context.this.table_input_stream()?;
+    match (input.recv(), cfg.default) {
+        (Ok(row), _) => context.output.send(row_value(row, input.types())),
+        (Err(_), Some(default)) => context.output.send(default),
+        (Err(e), None) => Err(e),
+    }
+}
@@ -9,6 +9,7 @@ use lazy_static::lazy_static;
 use ordered_map::OrderedMap;
 use signature::signature;
 use crate::lang::ordered_string_map::OrderedStringMap;
+use crate::lang::data::table::Row;
 
 lazy_static! {
     pub static ref METHODS: OrderedMap<String, Command> = {
@@ -16,6 +17,7 @@ lazy_static! {
         let path = vec!["global", "types", "table_output_stream"];
         Call::declare_method(&mut res, &path);
         Write::declare_method(&mut res, &path);
+        Push::declare_method(&mut res, &path);
         res
     };
 }
@@ -69,3 +71,42 @@ fn write(context: CommandContext) -> CrushResult<()> {
     }
     Ok(())
 }
+
+#[signature(
+push,
+can_block = true,
+output = Known(ValueType::Empty),
+short = "Write a single row to this output stream",
+long = "The arguments are the cells of the row, and must match the columns of the stream.",
+long = "Blocks if the stream is full.",
+example = "pipe:output:push 42",
+)]
+struct Push {
+    #[unnamed()]
+    #[description("the cells of the row.")]
+    cells: Vec<Value>,
+}
+
+fn push(context: CommandContext) -> CrushResult<()> {
+    let cfg: Push = Push::parse(context.arguments, &context.global_state.printer())?;
+    let output = context.this.table_output_stream()?;
+    if cfg.cells.len() != output.types().len() {
+        return argument_error_legacy(format!(
+            "Expected {} cells, got {}",
+            output.types().len(),
+            cfg.cells.len()
+        ));
+    }
+    for (cell, column) in cfg.cells.iter().zip(output.types()) {
+        if !column.cell_type.is(cell) {
+            return argument_error_legacy(format!(
+                "Wrong type for column {}, expected {}, got {}",
+                column.name,
+                column.cell_type.to_string(),
+                cell.value_type().to_string()
+            ));
+        }
+    }
+    output.send(Row::new(cfg.cells))?;
+    context.output.send(Value::Empty())
+}
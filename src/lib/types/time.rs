@@ -1,5 +1,5 @@
 use crate::lang::command::Command;
-use crate::lang::command::OutputType::Known;
+use crate::lang::command::OutputType::{Known, Unknown};
 use crate::lang::command::TypeMap;
 use crate::lang::errors::{argument_error_legacy, to_crush_error, CrushResult};
 use crate::lang::execution_context::{ArgumentVector, This};
@@ -17,7 +17,7 @@ fn full(name: &'static str) -> Vec<&'static str> {
 lazy_static! {
     pub static ref METHODS: OrderedMap<String, Command> = {
         let mut res: OrderedMap<String, Command> = OrderedMap::new();
-        let path = vec!["global", "types", "string"];
+        let path = vec!["global", "types", "time"];
         res.declare(
             full("__add__"),
             add,
@@ -32,10 +32,10 @@ lazy_static! {
             full("__sub__"),
             sub,
             false,
-            "time - delta:duration",
-            "Remove the specified delta from this time",
+            "time - (delta:duration | time:time)",
+            "Remove the specified delta from this time, or return the time elapsed since the specified time",
             None,
-            Known(ValueType::Time),
+            Unknown,
             vec![],
         );
         Now::declare_method(&mut res, &path);
@@ -1,9 +1,13 @@
 use crate::lang::command::OutputType::{Known, Unknown};
-use crate::lang::errors::{argument_error_legacy, mandate, CrushResult};
-use crate::lang::execution_context::CommandContext;
+use crate::lang::errors::{argument_error_legacy, error, mandate, CrushResult};
+use crate::lang::execution_context::{ArgumentVector, CommandContext};
 use crate::lang::data::scope::Scope;
 use crate::lang::data::table::{ColumnType, Row};
 use crate::lang::value::{Value, ValueType};
+use signature::signature;
+use crate::lang::ast::TrackedString;
+use crate::lang::command::Parameter;
+use crate::lang::value::ValueDefinition;
 
 pub fn r#let(context: CommandContext) -> CrushResult<()> {
     for arg in context.arguments {
@@ -15,6 +19,35 @@ pub fn r#let(context: CommandContext) -> CrushResult<()> {
     context.output.send(Value::Empty())
 }
 
+pub fn declare_typed(mut context: CommandContext) -> CrushResult<()> {
+    context.arguments.check_len(3)?;
+    let name = context.arguments.string(0)?;
+    let value_type = context.arguments.r#type(1)?;
+    let value = context.arguments.value(2)?;
+    context.scope.declare_typed(&name, value_type, value)?;
+    context.output.send(Value::Empty())
+}
+
+pub fn r#const(context: CommandContext) -> CrushResult<()> {
+    for arg in context.arguments {
+        context.scope.declare_constant(
+            mandate(arg.argument_type, "Missing variable name")?.as_ref(),
+            arg.value,
+        )?;
+    }
+    context.output.send(Value::Empty())
+}
+
+pub fn local(context: CommandContext) -> CrushResult<()> {
+    for arg in context.arguments {
+        context.scope.redeclare(
+            mandate(arg.argument_type, "Missing variable name")?.as_ref(),
+            arg.value,
+        )?;
+    }
+    context.output.send(Value::Empty())
+}
+
 pub fn set(context: CommandContext) -> CrushResult<()> {
     for arg in context.arguments {
         context.scope.set(
@@ -27,19 +60,97 @@ pub fn set(context: CommandContext) -> CrushResult<()> {
 
 pub fn unset(context: CommandContext) -> CrushResult<()> {
     for arg in context.arguments {
-        if let Value::String(s) = &arg.value {
-            if s.len() == 0 {
-                return argument_error_legacy("Illegal variable name");
-            } else {
-                context.scope.remove_str(s)?;
-            }
-        } else {
-            return argument_error_legacy("Illegal variable name");
+        let path = match &arg.value {
+            Value::String(s) if !s.is_empty() => s
+                .split(':')
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>(),
+            Value::Field(f) if !f.is_empty() => f.clone(),
+            _ => return argument_error_legacy("Illegal variable name"),
+        };
+        if context.scope.remove(&path)?.is_none() {
+            return error(format!("Unknown variable {}", path.join(":")));
         }
     }
     context.output.send(Value::Empty())
 }
 
+pub fn def(mut context: CommandContext) -> CrushResult<()> {
+    context.arguments.check_len_min(2)?;
+    let name = context.arguments.remove(0);
+    let name = match name.value {
+        Value::String(s) => TrackedString::from(&s, name.location),
+        _ => return argument_error_legacy("Expected the name of the command to be a string"),
+    };
+    let body = context.arguments.command(context.arguments.len() - 1)?;
+    context.arguments.pop();
+
+    let signature = if context.arguments.is_empty() {
+        None
+    } else {
+        let mut parameters = Vec::new();
+        for arg in context.arguments.drain(..) {
+            match arg.value {
+                Value::String(s) => parameters.push(Parameter::Parameter(
+                    TrackedString::from(&s, arg.location),
+                    ValueDefinition::Value(Value::Type(ValueType::Any), arg.location),
+                    None,
+                )),
+                v => return argument_error_legacy(format!(
+                    "Expected parameter names to be strings, got a {}", v.value_type())),
+            }
+        }
+        Some(parameters)
+    };
+
+    context.scope.declare(&name.string.clone(), Value::Command(body.define(name, signature)?))?;
+    context.output.send(Value::Empty())
+}
+
+pub fn env(context: CommandContext) -> CrushResult<()> {
+    list_variables(context, false)
+}
+
+#[signature(
+uses,
+can_block = false,
+short = "Returns a table of the scopes that are searched when looking up variables",
+long = "These are the scopes that have been imported into the current scope or any of its parents",
+long = "via the use command, in the order they are searched.",
+example = "var:uses | select ^name")]
+struct Uses {}
+
+fn uses(context: CommandContext) -> CrushResult<()> {
+    let output = context.output.initialize(vec![
+        ColumnType::new("name", ValueType::String),
+        ColumnType::new("scope", ValueType::Scope),
+    ])?;
+    for scope in context.scope.uses()? {
+        let name = scope.full_path()
+            .map(|p| p.join(":"))
+            .unwrap_or_else(|_| "<anonymous>".to_string());
+        output.send(Row::new(vec![Value::String(name), Value::Scope(scope)]))?;
+    }
+    Ok(())
+}
+
+#[signature(
+vars,
+can_block = true,
+short = "Returns a table of the variables in the current scope, sorted by name",
+long = "The columns of the table are the name, and the type of the value.",
+example = "var:vars --local")]
+struct Vars {
+    #[description("only list variables declared in the current scope, not in parent scopes.")]
+    #[default(false)]
+    local: bool,
+}
+
+fn vars(context: CommandContext) -> CrushResult<()> {
+    let cfg: Vars = Vars::parse(context.arguments.clone(), &context.global_state.printer())?;
+    list_variables(context, cfg.local)
+}
+
 pub fn r#use(context: CommandContext) -> CrushResult<()> {
     for arg in context.arguments.iter() {
         match (arg.argument_type.is_none(), &arg.value) {
@@ -50,13 +161,17 @@ pub fn r#use(context: CommandContext) -> CrushResult<()> {
     context.output.send(Value::Empty())
 }
 
-pub fn env(context: CommandContext) -> CrushResult<()> {
+fn list_variables(context: CommandContext, local: bool) -> CrushResult<()> {
     let output = context.output.initialize(vec![
         ColumnType::new("name", ValueType::String),
         ColumnType::new("type", ValueType::String),
     ])?;
 
-    let values = context.scope.dump()?;
+    let values = if local {
+        context.scope.dump_local()?
+    } else {
+        context.scope.dump()?
+    };
 
     let mut keys = values.keys().collect::<Vec<&String>>();
     keys.sort();
@@ -84,6 +199,46 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
                 Known(ValueType::Empty),
                 vec![],
             )?;
+            ns.declare_command(
+                "const", r#const, false,
+                "const name=value",
+                "Declare a new constant",
+                Some(r#"    Constants are variables that can not be reassigned or removed.
+
+    Example:
+
+    var:const answer=42"#),
+                Known(ValueType::Empty),
+                vec![],
+            )?;
+            ns.declare_command(
+                "declare", declare_typed, false,
+                "declare name:string type:type value:any",
+                "Declare a new variable with an explicit type",
+                Some(r#"    The variable can later be reassigned to any value of the declared type. Declare
+    a variable of type any to allow reassigning it to values of any type.
+
+    Example:
+
+    var:declare "x" any 1
+    x = "one""#),
+                Known(ValueType::Empty),
+                vec![],
+            )?;
+            ns.declare_command(
+                "local", local, false,
+                "local name=value",
+                "Declare a variable in the current scope, shadowing any variable of the same name in a parent scope",
+                Some(r#"    Unlike let, an existing variable of the same name in the current scope is replaced.
+
+    Example:
+
+    x := 1
+    {var:local x=2; echo x}
+    echo x"#),
+                Known(ValueType::Empty),
+                vec![],
+            )?;
             ns.declare_command(
                 "set", set, false,
                 "name = value",
@@ -94,19 +249,39 @@ pub fn declare(root: &Scope) -> CrushResult<()> {
             )?;
             ns.declare_command(
                 "unset", unset, false,
-                "scope name:string",
-                "Removes a variable from the namespace",
-                None,
+                "unset name:(string|field)...",
+                "Removes one or more variables from the namespace",
+                Some(r#"    It is an error to unset a variable that does not exist.
+
+    Example:
+
+    var:unset "x" ^y"#),
                 Known(ValueType::Empty),
                 vec![],
             )?;
             ns.declare_command(
-                "env", env, false,
+                "env", env, true,
                 "env", "Returns a table containing the current namespace",
                 Some(r#"    The columns of the table are the name, and the type of the value."#),
                 Unknown,
                 vec![],
             )?;
+            ns.declare_command(
+                "def", def, false,
+                "def name:string [parameter:string]... body:command",
+                "Declare a new named command",
+                Some(r#"    The parameters are bound to the unnamed arguments of the command, in order.
+    If no parameters are given, the signature of the closure is used, if any.
+
+    Example:
+
+    var:def "greet" "name" {echo ("Hello, {}":format name)}
+    greet "world""#),
+                Known(ValueType::Empty),
+                vec![],
+            )?;
+            Vars::declare(ns)?;
+            Uses::declare(ns)?;
             ns.declare_command(
                 "use", r#use, false,
                 "use scope:scope",
@@ -1,7 +1,9 @@
 /**
 A simple wrapper around std::fs::read_dir to allow for unit testing via fakes.
 
-There are two implementations, the real one can be instantiated via the directory_lister() function.
+There are two implementations, the real one can be instantiated via the directory_lister() function,
+or via symlink_following_directory_lister() if symbolic links to directories should be treated as
+directories.
 There is also a fake lister for tests, accessible via FakeDirectoryLister::new().
 
 It only allows you to list files and check if they are a directory, so if you need full metadata,
@@ -10,7 +12,7 @@ you'll need something cleverer.
 
 use std::path::{PathBuf};
 use crate::lang::errors::{CrushResult, to_crush_error, mandate};
-use std::fs::{ReadDir, read_dir};
+use std::fs::{ReadDir, read_dir, metadata};
 use ordered_map::{OrderedMap, Entry};
 use std::collections::VecDeque;
 
@@ -33,10 +35,16 @@ pub trait DirectoryLister {
     fn list(&self, path: impl Into<PathBuf>) -> CrushResult<Self::DirectoryIter>;
 }
 
-pub struct RealDirectoryLister {}
+pub struct RealDirectoryLister {
+    follow_symlinks: bool,
+}
 
 pub fn directory_lister() -> RealDirectoryLister {
-    RealDirectoryLister {}
+    RealDirectoryLister { follow_symlinks: false }
+}
+
+pub fn symlink_following_directory_lister() -> RealDirectoryLister {
+    RealDirectoryLister { follow_symlinks: true }
 }
 
 impl DirectoryLister for RealDirectoryLister {
@@ -46,6 +54,7 @@ impl DirectoryLister for RealDirectoryLister {
         Ok(
             RealIter {
                 read_dir: to_crush_error(read_dir(&path.into()))?,
+                follow_symlinks: self.follow_symlinks,
             }
         )
     }
@@ -53,6 +62,7 @@ impl DirectoryLister for RealDirectoryLister {
 
 pub struct RealIter {
     read_dir: ReadDir,
+    follow_symlinks: bool,
 }
 
 impl Iterator for RealIter {
@@ -65,10 +75,15 @@ impl Iterator for RealIter {
                 return None when read_dir returns None to terminate iteration
              */
             if let Ok(next) = self.read_dir.next()? {
+                let metadata = if self.follow_symlinks {
+                    metadata(next.path())
+                } else {
+                    next.metadata()
+                };
                 return Some(Directory {
                     name: PathBuf::from(next.file_name()),
                     full_path: PathBuf::from(next.path()),
-                    is_directory: next.metadata().map(|m| m.is_dir()).unwrap_or(false),
+                    is_directory: metadata.map(|m| m.is_dir()).unwrap_or(false),
                 });
             }
         }
@@ -2,7 +2,7 @@ use crate::lang::errors::{CrushResult, data_error};
 use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
 use std::fmt::{Display, Formatter};
-use crate::util::directory_lister::{directory_lister, DirectoryLister};
+use crate::util::directory_lister::{directory_lister, symlink_following_directory_lister, DirectoryLister};
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
 pub struct Glob {
@@ -31,12 +31,18 @@ impl Display for Glob {
 }
 
 fn compile(s: &str) -> Vec<Tile> {
+    compile_with_wildcard(s, '%')
+}
+
+/// Compile a pattern where the specified character is the wildcard. Every other character
+/// except `?` matches only itself.
+fn compile_with_wildcard(s: &str, wildcard: char) -> Vec<Tile> {
     let mut res = Vec::new();
     let mut was_any = false;
     for c in s.chars() {
         if was_any {
             match c {
-                '%' => res.push(Tile::Recursive),
+                c if c == wildcard => res.push(Tile::Recursive),
                 '?' => {
                     res.push(Tile::Any);
                     res.push(Tile::Single);
@@ -49,7 +55,7 @@ fn compile(s: &str) -> Vec<Tile> {
             was_any = false;
         } else {
             match c {
-                '%' => was_any = true,
+                c if c == wildcard => was_any = true,
                 '?' => {
                     res.push(Tile::Single);
                 }
@@ -73,6 +79,15 @@ impl Glob {
         }
     }
 
+    /// Create a glob from a traditional shell style pattern, where `*` matches any
+    /// sequence of characters except `/` and `**` matches any sequence of characters.
+    pub fn from_shell_pattern(def: &str) -> Glob {
+        Glob {
+            original: def.to_string(),
+            pattern: compile_with_wildcard(def, '*'),
+        }
+    }
+
     pub fn matches(&self, v: &str) -> bool {
         glob_match(&self.pattern, v).matches
     }
@@ -80,6 +95,10 @@ impl Glob {
     pub fn glob_files(&self, cwd: &Path, out: &mut Vec<PathBuf>) -> CrushResult<()> {
         glob_files(&self.pattern, cwd, out, &directory_lister())
     }
+
+    pub fn glob_files_following_symlinks(&self, cwd: &Path, out: &mut Vec<PathBuf>) -> CrushResult<()> {
+        glob_files(&self.pattern, cwd, out, &symlink_following_directory_lister())
+    }
 }
 
 fn glob_files(pattern: &[Tile], cwd: &Path, out: &mut Vec<PathBuf>, lister: &impl DirectoryLister) -> CrushResult<()> {
@@ -392,6 +411,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_shell_pattern() {
+        assert!(Glob::from_shell_pattern("a/*.rs").matches("a/b.rs"));
+        assert!(!Glob::from_shell_pattern("a/*.rs").matches("a/b/c.rs"));
+        assert!(Glob::from_shell_pattern("a/**.rs").matches("a/b/c.rs"));
+        assert!(Glob::from_shell_pattern("a/?.rs").matches("a/b.rs"));
+        assert!(Glob::from_shell_pattern("100%.txt").matches("100%.txt"));
+        assert!(!Glob::from_shell_pattern("100%.txt").matches("1000.txt"));
+        assert!(Glob::from_shell_pattern("*%").matches("a%"));
+        assert!(!Glob::from_shell_pattern("*%").matches("a"));
+    }
+
     fn lister() -> FakeDirectoryLister {
         let mut res = FakeDirectoryLister::new("/home/rabbit");
         res.add("example_data/tree", &vec!["a"])
@@ -1,4 +1,5 @@
 use chrono::Duration;
+use crate::lang::errors::{argument_error_legacy, data_error, CrushResult};
 
 pub fn duration_format(d: &Duration) -> String {
     const MICROS_IN_SECOND: i128 = 1_000_000_000;
@@ -59,3 +60,110 @@ pub fn duration_format(d: &Duration) -> String {
     }
     res
 }
+
+fn unit_nanoseconds(unit: &str) -> Option<i128> {
+    match unit {
+        "ns" => Some(1),
+        "us" => Some(1_000),
+        "ms" => Some(1_000_000),
+        "s" => Some(1_000_000_000),
+        "m" => Some(60 * 1_000_000_000),
+        "h" => Some(60 * 60 * 1_000_000_000),
+        "d" => Some(24 * 60 * 60 * 1_000_000_000),
+        "w" => Some(7 * 24 * 60 * 60 * 1_000_000_000),
+        _ => None,
+    }
+}
+
+/// Parse a duration like "1h30m", "500ms" or "2d". A plain number is
+/// interpreted as a number of seconds.
+pub fn parse_duration(s: &str) -> CrushResult<Duration> {
+    let invalid = || argument_error_legacy(format!(
+        "Invalid duration \"{}\", expected e.g. \"1h30m\" or \"500ms\"", s));
+    let out_of_range = || data_error(format!("Duration \"{}\" is out of range", s));
+    let (negative, body) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    if body.is_empty() {
+        return invalid();
+    }
+
+    let mut nanos: i128 = 0;
+    if let Ok(seconds) = body.parse::<i128>() {
+        nanos = match seconds.checked_mul(1_000_000_000) {
+            Some(n) => n,
+            None => return out_of_range(),
+        };
+    } else {
+        let mut rest = body;
+        while !rest.is_empty() {
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let units = rest[digits..].find(|c: char| c.is_ascii_digit()).map(|i| i + digits).unwrap_or(rest.len());
+            if digits == 0 || digits == units {
+                return invalid();
+            }
+            let unit = match unit_nanoseconds(&rest[digits..units]) {
+                Some(u) => u,
+                None => return invalid(),
+            };
+            let amount = match rest[..digits].parse::<i128>() {
+                Ok(a) => a,
+                Err(_) => return out_of_range(),
+            };
+            nanos = match amount.checked_mul(unit).and_then(|n| n.checked_add(nanos)) {
+                Some(n) => n,
+                None => return out_of_range(),
+            };
+            rest = &rest[units..];
+        }
+    }
+
+    // Durations are stored as a number of milliseconds in an i64, so check the range before
+    // constructing one, since the Duration constructors panic on overflow.
+    if nanos / 1_000_000 > Duration::max_value().num_milliseconds() as i128 {
+        return out_of_range();
+    }
+    let res = match Duration::seconds((nanos / 1_000_000_000) as i64)
+        .checked_add(&Duration::nanoseconds((nanos % 1_000_000_000) as i64)) {
+        Some(d) => d,
+        None => return out_of_range(),
+    };
+    Ok(if negative { -res } else { res })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_simple_durations() {
+        assert_eq!(parse_duration("2d").unwrap(), Duration::days(2));
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::milliseconds(500));
+        assert_eq!(parse_duration("15").unwrap(), Duration::seconds(15));
+    }
+
+    #[test]
+    fn parse_combined_durations() {
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration("1m1s1ms").unwrap(), Duration::milliseconds(61_001));
+        assert_eq!(parse_duration("-1h").unwrap(), Duration::hours(-1));
+    }
+
+    #[test]
+    fn reject_invalid_durations() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("3 days").is_err());
+        assert!(parse_duration("1x").is_err());
+    }
+
+    #[test]
+    fn reject_out_of_range_durations() {
+        assert!(parse_duration("10000000000000000").is_err());
+        assert!(parse_duration("100000000000000000000000000000000000000000s").is_err());
+        assert!(parse_duration("20000000000000w").is_err());
+        assert!(parse_duration("9223372036854775807s1s").is_err());
+    }
+}